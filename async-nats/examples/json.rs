@@ -1,6 +1,5 @@
 use futures::stream::StreamExt;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::env;
 
 // Use the macro to generate the serialize and deserialize methods on
@@ -19,23 +18,22 @@ async fn main() -> Result<(), async_nats::Error> {
 
     let client = async_nats::connect(nats_url).await?;
 
-    // Create a subscription that handles one message.
-    let mut subscriber = client.subscribe("foo".into()).await?.take(1);
+    // Create a subscription that decodes JSON payloads and handles one message.
+    let mut subscriber = client
+        .subscribe_typed::<Payload>("foo".into())
+        .await?
+        .take(1);
 
-    // Construct a Payload value and serialize it.
+    // Construct a Payload value and publish it serialized as JSON.
     let payload = Payload {
         foo: "bar".to_string(),
         bar: 27,
     };
-    let bytes = serde_json::to_vec(&json!(payload))?;
-
-    // Publish the serialized payload.
-    client.publish("foo".into(), bytes.into()).await?;
+    client.publish_json("foo".into(), &payload).await?;
 
     while let Some(message) = subscriber.next().await {
-        // Deserialize the message payload into a Payload value.
-        // let payload: Payload = serde_json::from_slice(message.payload.as_ref())?;
-        if let Ok(payload) = serde_json::from_slice::<Payload>(&message.payload) {
+        // The payload was deserialized into a Payload value by the subscriber.
+        if let Ok(payload) = message.value {
             println!(
                 "received payload: foo={:?} bar={:?}",
                 payload.foo, payload.bar
@@ -0,0 +1,87 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Built-in strategies for [ConnectOptions::reconnect_delay_callback][crate::ConnectOptions::reconnect_delay_callback].
+//!
+//! Each strategy takes the number of failed attempts to connect to a server and returns
+//! how long to wait before the next attempt.
+
+use rand::Rng;
+use std::cmp;
+use std::time::Duration;
+
+const MAX_DELAY: Duration = Duration::from_secs(4);
+
+/// Exponential backoff: 1ms, 2ms, 4ms, 8ms, ..., capped at 4 seconds.
+///
+/// Clients disconnected at the same time retry in lockstep, so prefer [full_jitter]
+/// when many clients connect to the same servers.
+pub fn exponential(attempts: usize) -> Duration {
+    let exp: u32 = attempts.try_into().unwrap_or(u32::MAX);
+
+    cmp::min(Duration::from_millis(2_u64.saturating_pow(exp)), MAX_DELAY)
+}
+
+/// Exponential backoff with full jitter: a random delay between zero and [exponential].
+/// Jitter is applied from the first attempt, so that clients disconnected at the same time
+/// do not reconnect in lockstep.
+///
+/// This is the default strategy.
+pub fn full_jitter(attempts: usize) -> Duration {
+    rand::thread_rng().gen_range(Duration::ZERO..=exponential(attempts))
+}
+
+/// A constant delay for every attempt but the first, which is not delayed.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// async_nats::ConnectOptions::new()
+///     .reconnect_delay_callback(async_nats::backoff::constant(std::time::Duration::from_secs(1)))
+///     .connect("demo.nats.io").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn constant(delay: Duration) -> impl Fn(usize) -> Duration + Send + Sync + 'static {
+    move |attempts| {
+        if attempts == 0 {
+            Duration::ZERO
+        } else {
+            delay
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        assert_eq!(exponential(0), Duration::from_millis(1));
+        assert_eq!(exponential(1), Duration::from_millis(2));
+        assert_eq!(exponential(4), Duration::from_millis(16));
+        assert_eq!(exponential(100), MAX_DELAY);
+    }
+
+    #[test]
+    fn full_jitter_backoff() {
+        for attempts in 0..20 {
+            assert!(full_jitter(attempts) <= exponential(attempts));
+        }
+
+        let first = full_jitter(0);
+        assert!((0..100).any(|_| full_jitter(0) != first));
+    }
+}
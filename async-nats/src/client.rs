@@ -11,20 +11,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::ServerInfo;
+use crate::queue;
+use crate::statistics::{Counters, Statistics};
+use crate::{ConnectionState, ServerInfo};
 
-use super::{header::HeaderMap, status::StatusCode, Command, Error, Message, Subscriber};
+use super::{header::HeaderMap, status::StatusCode, Command, Handler, Message, Subscriber};
 use bytes::Bytes;
-use futures::future::TryFutureExt;
-use futures::stream::StreamExt;
+use futures::future::{Future, TryFutureExt};
+use futures::stream::{Stream, StreamExt};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::error;
 use std::fmt;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{self, ErrorKind};
-use tokio::sync::mpsc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 
 lazy_static! {
     static ref VERSION_RE: Regex = Regex::new(r#"\Av?([0-9]+)\.?([0-9]+)?\.?([0-9]+)?"#).unwrap();
@@ -32,7 +36,7 @@ lazy_static! {
 
 /// An error returned from the [`Client::publish`], [`Client::publish_with_headers`],
 /// [`Client::publish_with_reply`] or [`Client::publish_with_reply_and_headers`] functions.
-pub struct PublishError(mpsc::error::SendError<Command>);
+pub struct PublishError(());
 
 impl fmt::Debug for PublishError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -48,31 +52,196 @@ impl fmt::Display for PublishError {
 
 impl error::Error for PublishError {}
 
+impl From<mpsc::error::SendError<Command>> for PublishError {
+    fn from(_: mpsc::error::SendError<Command>) -> Self {
+        PublishError(())
+    }
+}
+
+/// An error returned from the [`Client::subscribe`] or [`Client::queue_subscribe`] functions.
+pub struct SubscribeError(());
+
+impl fmt::Debug for SubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubscribeError").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for SubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "subscribing on a closed client".fmt(f)
+    }
+}
+
+impl error::Error for SubscribeError {}
+
+impl From<mpsc::error::SendError<Command>> for SubscribeError {
+    fn from(_: mpsc::error::SendError<Command>) -> Self {
+        SubscribeError(())
+    }
+}
+
+/// An error returned from the [`Client::flush`] function.
+pub struct FlushError(());
+
+impl fmt::Debug for FlushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlushError").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for FlushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "flushing a closed client".fmt(f)
+    }
+}
+
+impl error::Error for FlushError {}
+
+/// An error returned from the [`Subscriber::unsubscribe`], [`Subscriber::unsubscribe_after`]
+/// or [`Handler::unsubscribe`][crate::Handler::unsubscribe] functions.
+pub struct UnsubscribeError(());
+
+impl UnsubscribeError {
+    pub(crate) fn new() -> UnsubscribeError {
+        UnsubscribeError(())
+    }
+}
+
+impl fmt::Debug for UnsubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnsubscribeError").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for UnsubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "unsubscribing on a closed client".fmt(f)
+    }
+}
+
+impl error::Error for UnsubscribeError {}
+
+impl From<mpsc::error::SendError<Command>> for UnsubscribeError {
+    fn from(_: mpsc::error::SendError<Command>) -> Self {
+        UnsubscribeError(())
+    }
+}
+
+/// An error returned from the [`Client::drain`], [`Subscriber::drain`] or
+/// [`Handler::drain`][crate::Handler::drain] functions.
+pub struct DrainError(());
+
+impl DrainError {
+    pub(crate) fn new() -> DrainError {
+        DrainError(())
+    }
+}
+
+impl fmt::Debug for DrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainError").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for DrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "draining a closed client".fmt(f)
+    }
+}
+
+impl error::Error for DrainError {}
+
+/// An error returned from the [`Client::request`], [`Client::request_with_headers`],
+/// [`Client::send_request`] or [`Client::request_multi`] functions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RequestError {
+    /// There are no subscribers listening on the request subject.
+    NoResponders,
+    /// No response arrived within the request timeout.
+    TimedOut,
+    /// The client was closed before a response arrived.
+    Closed,
+    /// Publishing the request failed.
+    Publish(PublishError),
+    /// Subscribing to the response inbox failed.
+    Subscribe(SubscribeError),
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::NoResponders => write!(f, "nats: no responders"),
+            RequestError::TimedOut => write!(f, "nats: request timed out"),
+            RequestError::Closed => write!(f, "nats: client closed before a response arrived"),
+            RequestError::Publish(err) => write!(f, "nats: failed to publish request: {}", err),
+            RequestError::Subscribe(err) => {
+                write!(f, "nats: failed to subscribe for response: {}", err)
+            }
+        }
+    }
+}
+
+impl error::Error for RequestError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RequestError::Publish(err) => Some(err),
+            RequestError::Subscribe(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PublishError> for RequestError {
+    fn from(err: PublishError) -> Self {
+        RequestError::Publish(err)
+    }
+}
+
+impl From<SubscribeError> for RequestError {
+    fn from(err: SubscribeError) -> Self {
+        RequestError::Subscribe(err)
+    }
+}
+
 /// Client is a `Clonable` handle to NATS connection.
 /// Client should not be created directly. Instead, one of two methods can be used:
 /// [crate::connect] and [crate::ConnectOptions::connect]
 #[derive(Clone, Debug)]
 pub struct Client {
     info: tokio::sync::watch::Receiver<ServerInfo>,
+    state: tokio::sync::watch::Receiver<ConnectionState>,
     sender: mpsc::Sender<Command>,
     next_subscription_id: Arc<AtomicU64>,
-    subscription_capacity: usize,
+    subscription_limits: queue::Limits,
     inbox_prefix: String,
+    multiplexer_prefix: String,
+    request_timeout: Option<Duration>,
+    statistics: Arc<Counters>,
 }
 
 impl Client {
     pub(crate) fn new(
         info: tokio::sync::watch::Receiver<ServerInfo>,
+        state: tokio::sync::watch::Receiver<ConnectionState>,
         sender: mpsc::Sender<Command>,
-        capacity: usize,
+        subscription_limits: queue::Limits,
         inbox_prefix: String,
+        request_timeout: Option<Duration>,
+        statistics: Arc<Counters>,
     ) -> Client {
         Client {
             info,
+            state,
             sender,
-            next_subscription_id: Arc::new(AtomicU64::new(0)),
-            subscription_capacity: capacity,
+            // sid `0` is reserved for the shared response inbox.
+            next_subscription_id: Arc::new(AtomicU64::new(1)),
+            subscription_limits,
+            multiplexer_prefix: format!("{}.{}.", inbox_prefix, nuid::next()),
             inbox_prefix,
+            request_timeout,
+            statistics,
         }
     }
 
@@ -93,7 +262,61 @@ impl Client {
         self.info.borrow().to_owned()
     }
 
+    /// Returns the current state of the connection.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main () -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// assert_eq!(client.connection_state(), async_nats::ConnectionState::Connected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Returns a watcher notified about every change of the connection state.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main () -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut state = client.connection_state_watcher();
+    /// while state.changed().await.is_ok() {
+    ///     println!("connection is {}", *state.borrow());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_state_watcher(&self) -> tokio::sync::watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    /// Returns a snapshot of the connection statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main () -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let statistics = client.statistics();
+    /// println!("published {} messages", statistics.out_messages);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn statistics(&self) -> Statistics {
+        self.statistics.snapshot()
+    }
+
     /// Returns true if the server version is compatible with the version components.
+    /// Returns false while the server version is unknown, e.g. before the first connection
+    /// was established with [crate::ConnectOptions::retry_on_failed_connect].
     ///
     /// # Examples
     ///
@@ -108,22 +331,22 @@ impl Client {
     pub fn is_server_compatible(&self, major: i64, minor: i64, patch: i64) -> bool {
         let info = self.server_info();
 
-        let server_version_captures = VERSION_RE.captures(&info.version).unwrap();
+        let server_version_captures = match VERSION_RE.captures(&info.version) {
+            Some(captures) => captures,
+            None => return false,
+        };
+        let component = |index| {
+            server_version_captures
+                .get(index)
+                .and_then(|m| m.as_str().parse::<i64>().ok())
+        };
 
-        let server_major = server_version_captures
-            .get(1)
-            .map(|m| m.as_str().parse::<i64>().unwrap())
-            .unwrap();
-
-        let server_minor = server_version_captures
-            .get(2)
-            .map(|m| m.as_str().parse::<i64>().unwrap())
-            .unwrap();
-
-        let server_patch = server_version_captures
-            .get(3)
-            .map(|m| m.as_str().parse::<i64>().unwrap())
-            .unwrap();
+        let server_major = match component(1) {
+            Some(major) => major,
+            None => return false,
+        };
+        let server_minor = component(2).unwrap_or_default();
+        let server_patch = component(3).unwrap_or_default();
 
         if server_major < major
             || (server_major == major && server_minor < minor)
@@ -134,6 +357,10 @@ impl Client {
         true
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "publish", skip_all, fields(subject = %subject))
+    )]
     pub async fn publish(&self, subject: String, payload: Bytes) -> Result<(), PublishError> {
         self.sender
             .send(Command::Publish {
@@ -142,17 +369,21 @@ impl Client {
                 respond: None,
                 headers: None,
             })
-            .map_err(PublishError)
+            .map_err(PublishError::from)
             .await?;
         Ok(())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "publish", skip_all, fields(subject = %subject))
+    )]
     pub async fn publish_with_headers(
         &self,
         subject: String,
         headers: HeaderMap,
         payload: Bytes,
-    ) -> Result<(), Error> {
+    ) -> Result<(), PublishError> {
         self.sender
             .send(Command::Publish {
                 subject,
@@ -160,17 +391,21 @@ impl Client {
                 respond: None,
                 headers: Some(headers),
             })
-            .map_err(PublishError)
+            .map_err(PublishError::from)
             .await?;
         Ok(())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "publish", skip_all, fields(subject = %subject))
+    )]
     pub async fn publish_with_reply(
         &self,
         subject: String,
         reply: String,
         payload: Bytes,
-    ) -> Result<(), Error> {
+    ) -> Result<(), PublishError> {
         self.sender
             .send(Command::Publish {
                 subject,
@@ -178,11 +413,15 @@ impl Client {
                 respond: Some(reply),
                 headers: None,
             })
-            .map_err(PublishError)
+            .map_err(PublishError::from)
             .await?;
         Ok(())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "publish", skip_all, fields(subject = %subject))
+    )]
     pub async fn publish_with_reply_and_headers(
         &self,
         subject: String,
@@ -197,61 +436,169 @@ impl Client {
                 respond: Some(reply),
                 headers: Some(headers),
             })
-            .map_err(PublishError)
+            .map_err(PublishError::from)
             .await?;
         Ok(())
     }
 
-    pub async fn request(&self, subject: String, payload: Bytes) -> Result<Message, Error> {
-        let inbox = self.new_inbox();
-        let mut sub = self.subscribe(inbox.clone()).await?;
-        self.publish_with_reply(subject, inbox, payload).await?;
-        self.flush().await?;
-        match sub.next().await {
-            Some(message) => {
-                if message.status == Some(StatusCode::NO_RESPONDERS) {
-                    return Err(Box::new(std::io::Error::new(
-                        ErrorKind::NotFound,
-                        "nats: no responders",
-                    )));
-                }
-                Ok(message)
-            }
-            None => Err(Box::new(io::Error::new(
-                ErrorKind::BrokenPipe,
-                "did not receive any message",
-            ))),
-        }
+    /// Sends the request with a payload and waits for the first response.
+    /// Fails if no response arrives within the default request timeout set with
+    /// [ConnectOptions::request_timeout][crate::ConnectOptions::request_timeout],
+    /// or if there are no responders on the subject.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let response = client.request("service".into(), "data".into()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request(&self, subject: String, payload: Bytes) -> Result<Message, RequestError> {
+        self.send_request(subject, Request::new().payload(payload))
+            .await
     }
 
+    /// Sends the request with headers and a payload and waits for the first response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut headers = async_nats::HeaderMap::new();
+    /// headers.insert("key", "value".parse().unwrap());
+    /// let response = client
+    ///     .request_with_headers("service".into(), headers, "data".into())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn request_with_headers(
         &self,
         subject: String,
         headers: HeaderMap,
         payload: Bytes,
-    ) -> Result<Message, Error> {
-        let inbox = self.new_inbox();
-        let mut sub = self.subscribe(inbox.clone()).await?;
-        self.publish_with_reply_and_headers(subject, inbox, headers, payload)
-            .await?;
-        self.flush().await?;
-        match sub.next().await {
+    ) -> Result<Message, RequestError> {
+        self.send_request(subject, Request::new().headers(headers).payload(payload))
+            .await
+    }
+
+    /// Sends a [Request] created with the request builder and waits for the first response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use std::time::Duration;
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let request = async_nats::Request::new()
+    ///     .payload("data".into())
+    ///     .timeout(Some(Duration::from_millis(500)));
+    /// let response = client.send_request("service".into(), request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "request", skip_all, fields(subject = %subject))
+    )]
+    pub async fn send_request(
+        &self,
+        subject: String,
+        request: Request,
+    ) -> Result<Message, RequestError> {
+        let timeout = request.timeout.unwrap_or(self.request_timeout);
+        let payload = request.payload.unwrap_or_default();
+
+        // Requests with a custom inbox need a dedicated subscription, all others share
+        // a single wildcard subscription.
+        let response = match request.inbox {
+            Some(inbox) => {
+                let mut sub = self.subscribe(inbox.clone()).await?;
+                match request.headers {
+                    Some(headers) => {
+                        self.publish_with_reply_and_headers(subject, inbox, headers, payload)
+                            .await?
+                    }
+                    None => self.publish_with_reply(subject, inbox, payload).await?,
+                }
+                self.try_flush().await?;
+
+                with_timeout(timeout, sub.next()).await?
+            }
+            None => {
+                let (sender, receiver) = oneshot::channel();
+                self.sender
+                    .send(Command::Request {
+                        subject,
+                        payload,
+                        respond: format!("{}{}", self.multiplexer_prefix, nuid::next()),
+                        headers: request.headers,
+                        sender,
+                    })
+                    .map_err(PublishError::from)
+                    .await?;
+
+                with_timeout(timeout, receiver).await?.ok()
+            }
+        };
+
+        match response {
             Some(message) => {
                 if message.status == Some(StatusCode::NO_RESPONDERS) {
-                    return Err(Box::new(std::io::Error::new(
-                        ErrorKind::NotFound,
-                        "nats: no responders",
-                    )));
+                    return Err(RequestError::NoResponders);
                 }
                 Ok(message)
             }
-            None => Err(Box::new(io::Error::new(
-                ErrorKind::BrokenPipe,
-                "did not receive any message",
-            ))),
+            None => Err(RequestError::Closed),
         }
     }
 
+    /// Sends the request with a payload and returns a [Responses] stream yielding every
+    /// response that arrives. The stream ends if there are no responders on the subject.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    /// use std::time::Duration;
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut responses = client.request_multi("service".into(), "data".into()).await?;
+    /// while let Ok(Some(response)) =
+    ///     tokio::time::timeout(Duration::from_secs(1), responses.next()).await
+    /// {
+    ///     println!("received response: {:?}", response);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "request_multi", skip_all, fields(subject = %subject))
+    )]
+    pub async fn request_multi(
+        &self,
+        subject: String,
+        payload: Bytes,
+    ) -> Result<Responses, RequestError> {
+        let inbox = self.new_inbox();
+        let subscriber = self.subscribe(inbox.clone()).await?;
+        self.publish_with_reply(subject, inbox, payload).await?;
+        self.try_flush().await?;
+
+        Ok(Responses {
+            subscriber,
+            done: false,
+        })
+    }
+
     /// Create a new globally unique inbox which can be used for replies.
     ///
     /// # Examples
@@ -268,9 +615,14 @@ impl Client {
         format!("{}.{}", self.inbox_prefix, nuid::next())
     }
 
-    pub async fn subscribe(&self, subject: String) -> Result<Subscriber, Error> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "subscribe", skip_all, fields(subject = %subject, sid))
+    )]
+    pub async fn subscribe(&self, subject: String) -> Result<Subscriber, SubscribeError> {
         let sid = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
-        let (sender, receiver) = mpsc::channel(self.subscription_capacity);
+        record!("sid", sid);
+        let (sender, receiver) = queue::channel(self.subscription_limits);
 
         self.sender
             .send(Command::Subscribe {
@@ -279,18 +631,24 @@ impl Client {
                 queue_group: None,
                 sender,
             })
+            .map_err(SubscribeError::from)
             .await?;
 
         Ok(Subscriber::new(sid, self.sender.clone(), receiver))
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "subscribe", skip_all, fields(subject = %subject, sid))
+    )]
     pub async fn queue_subscribe(
         &self,
         subject: String,
         queue_group: String,
-    ) -> Result<Subscriber, Error> {
+    ) -> Result<Subscriber, SubscribeError> {
         let sid = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
-        let (sender, receiver) = mpsc::channel(self.subscription_capacity);
+        record!("sid", sid);
+        let (sender, receiver) = queue::channel(self.subscription_limits);
 
         self.sender
             .send(Command::Subscribe {
@@ -299,16 +657,162 @@ impl Client {
                 queue_group: Some(queue_group),
                 sender,
             })
+            .map_err(SubscribeError::from)
             .await?;
 
         Ok(Subscriber::new(sid, self.sender.clone(), receiver))
     }
 
-    pub async fn flush(&self) -> Result<(), Error> {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        self.sender.send(Command::Flush { result: tx }).await?;
-        // first question mark is an error from rx itself, second for error from flush.
-        rx.await??;
-        Ok(())
+    /// Subscribes to the subject and handles its messages with the given closure in a
+    /// spawned task. See [Subscriber::with_handler] for details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let handler = client
+    ///     .subscribe_with_handler("events".into(), |message| async move {
+    ///         println!("received {:?}", message);
+    ///     })
+    ///     .await?;
+    /// # handler.unsubscribe().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_with_handler<F, Fut>(
+        &self,
+        subject: String,
+        handler: F,
+    ) -> Result<Handler, SubscribeError>
+    where
+        F: FnMut(Message) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Ok(self.subscribe(subject).await?.with_handler(handler))
+    }
+
+    /// Asks the connection handler to flush, without waiting for it.
+    async fn try_flush(&self) -> Result<(), PublishError> {
+        self.sender
+            .send(Command::TryFlush)
+            .await
+            .map_err(PublishError::from)
+    }
+
+    pub async fn flush(&self) -> Result<(), FlushError> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Command::Flush { result: tx })
+            .await
+            .map_err(|_| FlushError(()))?;
+        // the handler retries the flush after reconnecting, so it only fails once closed.
+        match rx.await {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(FlushError(())),
+        }
+    }
+
+    /// Drains the connection: unsubscribes all subscriptions while still delivering the
+    /// messages already sent to them, flushes pending publishes and closes the connection.
+    /// Returns once the connection is closed, after which the client can no longer be used.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// client.publish("events".into(), "data".into()).await?;
+    /// client.drain().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn drain(&self) -> Result<(), DrainError> {
+        let (result, closed) = oneshot::channel();
+        self.sender
+            .send(Command::DrainConnection { result })
+            .await
+            .map_err(|_| DrainError(()))?;
+        closed.await.map_err(|_| DrainError(()))
+    }
+}
+
+async fn with_timeout<F: std::future::Future>(
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<F::Output, RequestError> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| RequestError::TimedOut),
+        None => Ok(future.await),
+    }
+}
+
+/// Used for building customized requests, sent with [Client::send_request].
+#[derive(Default, Debug)]
+pub struct Request {
+    payload: Option<Bytes>,
+    headers: Option<HeaderMap>,
+    timeout: Option<Option<Duration>>,
+    inbox: Option<String>,
+}
+
+impl Request {
+    /// Creates a new, empty request.
+    pub fn new() -> Request {
+        Default::default()
+    }
+
+    /// Sets the payload of the request. If not used, empty payload will be sent.
+    pub fn payload(mut self, payload: Bytes) -> Request {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// Sets the headers of the request.
+    pub fn headers(mut self, headers: HeaderMap) -> Request {
+        self.headers = Some(headers);
+        self
+    }
+
+    /// Sets a custom timeout for the request, overriding the client default.
+    /// `None` means the request waits for a response indefinitely.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Request {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a custom inbox the response should be sent to.
+    /// If not used, a new unique inbox is created.
+    pub fn inbox(mut self, inbox: String) -> Request {
+        self.inbox = Some(inbox);
+        self
+    }
+}
+
+/// A [Stream] of responses returned by [Client::request_multi].
+/// It ends when the server reports there are no responders for the request.
+pub struct Responses {
+    subscriber: Subscriber,
+    done: bool,
+}
+
+impl Stream for Responses {
+    type Item = Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.subscriber.poll_next_unpin(cx) {
+            Poll::Ready(Some(message)) if message.status == Some(StatusCode::NO_RESPONDERS) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            other => other,
+        }
     }
 }
@@ -0,0 +1,577 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed payloads, encoded and decoded with a [Codec].
+//!
+//! Encoded messages carry the codec [CONTENT_TYPE][Codec::CONTENT_TYPE] in the
+//! `Content-Type` header. Messages without the header are decoded with the expected codec,
+//! while messages with a different media type fail to decode. Parameters such as
+//! `charset` are ignored.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), async_nats::Error> {
+//! use futures::StreamExt;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Event {
+//!     name: String,
+//! }
+//!
+//! let client = async_nats::connect("demo.nats.io").await?;
+//! let mut subscriber = client.subscribe_typed::<Event>("events".into()).await?;
+//!
+//! client
+//!     .publish_json(
+//!         "events".into(),
+//!         &Event {
+//!             name: "created".to_string(),
+//!         },
+//!     )
+//!     .await?;
+//!
+//! if let Some(event) = subscriber.next().await {
+//!     println!("received {:?} on {}", event.value?, event.message.subject);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::header::{HeaderMap, HeaderValue};
+use crate::{Client, Error, Message, RequestError, SubscribeError, Subscriber};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+const CONTENT_TYPE: &str = "Content-Type";
+
+/// Serializes and deserializes message payloads.
+pub trait Codec {
+    /// Value of the `Content-Type` header of the encoded messages.
+    const CONTENT_TYPE: &'static str;
+
+    fn encode<T: Serialize>(value: &T) -> Result<Bytes, Error>;
+
+    fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Error>;
+}
+
+/// JSON [Codec], using [serde_json].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Codec for Json {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Bytes, Error> {
+        Ok(serde_json::to_vec(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Error> {
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+/// MessagePack [Codec], using [rmp_serde]. Structs are encoded as maps.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MessagePack {
+    const CONTENT_TYPE: &'static str = "application/msgpack";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Bytes, Error> {
+        Ok(rmp_serde::to_vec_named(value)?.into())
+    }
+
+    fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Error> {
+        Ok(rmp_serde::from_slice(payload)?)
+    }
+}
+
+/// CBOR [Codec], using [ciborium].
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Codec for Cbor {
+    const CONTENT_TYPE: &'static str = "application/cbor";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Bytes, Error> {
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(value, &mut payload)?;
+        Ok(payload.into())
+    }
+
+    fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, Error> {
+        Ok(ciborium::de::from_reader(payload)?)
+    }
+}
+
+fn encode<C: Codec, T: Serialize>(value: &T) -> Result<(HeaderMap, Bytes), CodecError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(C::CONTENT_TYPE));
+    Ok((headers, C::encode(value).map_err(CodecError::Encode)?))
+}
+
+/// Returns the media type of a `Content-Type` value, without its parameters.
+fn media_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+/// An error returned when encoding or decoding a payload with a [Codec].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CodecError {
+    /// Encoding the value failed.
+    Encode(Error),
+    /// Decoding the payload failed.
+    Decode(Error),
+    /// The message has a `Content-Type` header with a different media type than the codec.
+    ContentType(String),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Encode(err) => write!(f, "nats: failed to encode payload: {}", err),
+            CodecError::Decode(err) => write!(f, "nats: failed to decode payload: {}", err),
+            CodecError::ContentType(content_type) => {
+                write!(f, "nats: unexpected content type: {}", content_type)
+            }
+        }
+    }
+}
+
+impl error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CodecError::Encode(err) | CodecError::Decode(err) => Some(err.as_ref()),
+            CodecError::ContentType(_) => None,
+        }
+    }
+}
+
+impl Message {
+    /// Decodes the payload with the codec, failing if the message has a `Content-Type`
+    /// header with a different media type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::codec::Json;
+    /// use futures::StreamExt;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut subscriber = client.subscribe("numbers".into()).await?;
+    /// if let Some(message) = subscriber.next().await {
+    ///     let numbers: Vec<u64> = message.decode::<Json, _>()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode<C: Codec, T: DeserializeOwned>(&self) -> Result<T, CodecError> {
+        let content_type = self
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(CONTENT_TYPE));
+        if let Some(content_type) = content_type {
+            if !media_type(content_type.as_str()).eq_ignore_ascii_case(C::CONTENT_TYPE) {
+                return Err(CodecError::ContentType(content_type.to_string()));
+            }
+        }
+
+        C::decode(&self.payload).map_err(CodecError::Decode)
+    }
+}
+
+impl Client {
+    /// Publishes the value encoded as JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// client.publish_json("numbers".into(), &vec![1, 2, 3]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish_json<T: Serialize>(
+        &self,
+        subject: String,
+        value: &T,
+    ) -> Result<(), PublishError> {
+        self.publish_with_codec::<Json, T>(subject, value).await
+    }
+
+    /// Publishes the value encoded with the codec.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::codec::Json;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// client
+    ///     .publish_with_codec::<Json, _>("numbers".into(), &vec![1, 2, 3])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish_with_codec<C: Codec, T: Serialize>(
+        &self,
+        subject: String,
+        value: &T,
+    ) -> Result<(), PublishError> {
+        let (headers, payload) = encode::<C, T>(value)?;
+        self.publish_with_headers(subject, headers, payload).await?;
+        Ok(())
+    }
+
+    /// Subscribes to the subject, decoding the messages as JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut subscriber = client.subscribe_typed::<Vec<u64>>("numbers".into()).await?;
+    /// while let Some(numbers) = subscriber.next().await {
+    ///     println!("received {:?}", numbers.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_typed<T: DeserializeOwned>(
+        &self,
+        subject: String,
+    ) -> Result<TypedSubscriber<T, Json>, SubscribeError> {
+        self.subscribe_with_codec::<Json, T>(subject).await
+    }
+
+    /// Subscribes to the subject, decoding the messages with the codec.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::codec::Json;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let subscriber = client
+    ///     .subscribe_with_codec::<Json, Vec<u64>>("numbers".into())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_with_codec<C: Codec, T: DeserializeOwned>(
+        &self,
+        subject: String,
+    ) -> Result<TypedSubscriber<T, C>, SubscribeError> {
+        Ok(TypedSubscriber::new(self.subscribe(subject).await?))
+    }
+
+    /// Sends the request encoded as JSON and decodes the first response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let sum: u64 = client.request_typed("sum".into(), &vec![1, 2, 3]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_typed<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        subject: String,
+        request: &Req,
+    ) -> Result<Resp, TypedRequestError> {
+        self.request_with_codec::<Json, Req, Resp>(subject, request)
+            .await
+    }
+
+    /// Sends the request encoded with the codec and decodes the first response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::codec::Json;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let sum: u64 = client
+    ///     .request_with_codec::<Json, _, _>("sum".into(), &vec![1, 2, 3])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_with_codec<C: Codec, Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        subject: String,
+        request: &Req,
+    ) -> Result<Resp, TypedRequestError> {
+        let (headers, payload) = encode::<C, Req>(request)?;
+        let response = self.request_with_headers(subject, headers, payload).await?;
+        Ok(response.decode::<C, Resp>()?)
+    }
+}
+
+/// An error returned from the [`Client::publish_json`] or [`Client::publish_with_codec`]
+/// functions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PublishError {
+    /// Encoding the value failed.
+    Codec(CodecError),
+    /// Publishing the message failed.
+    Publish(crate::PublishError),
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublishError::Codec(err) => err.fmt(f),
+            PublishError::Publish(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for PublishError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PublishError::Codec(err) => Some(err),
+            PublishError::Publish(err) => Some(err),
+        }
+    }
+}
+
+impl From<CodecError> for PublishError {
+    fn from(err: CodecError) -> Self {
+        PublishError::Codec(err)
+    }
+}
+
+impl From<crate::PublishError> for PublishError {
+    fn from(err: crate::PublishError) -> Self {
+        PublishError::Publish(err)
+    }
+}
+
+/// An error returned from the [`Client::request_typed`] or [`Client::request_with_codec`]
+/// functions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TypedRequestError {
+    /// Encoding the request or decoding the response failed.
+    Codec(CodecError),
+    /// Sending the request or receiving the response failed.
+    Request(RequestError),
+}
+
+impl fmt::Display for TypedRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedRequestError::Codec(err) => err.fmt(f),
+            TypedRequestError::Request(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for TypedRequestError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            TypedRequestError::Codec(err) => Some(err),
+            TypedRequestError::Request(err) => Some(err),
+        }
+    }
+}
+
+impl From<CodecError> for TypedRequestError {
+    fn from(err: CodecError) -> Self {
+        TypedRequestError::Codec(err)
+    }
+}
+
+impl From<RequestError> for TypedRequestError {
+    fn from(err: RequestError) -> Self {
+        TypedRequestError::Request(err)
+    }
+}
+
+/// A message decoded by a [TypedSubscriber].
+#[derive(Debug)]
+pub struct TypedMessage<T> {
+    /// The decoded payload, or the error if it could not be decoded.
+    pub value: Result<T, CodecError>,
+    /// The raw message.
+    pub message: Message,
+}
+
+/// A [Subscriber] decoding its messages with a [Codec], created with
+/// [Client::subscribe_typed] or [Client::subscribe_with_codec].
+pub struct TypedSubscriber<T, C = Json> {
+    subscriber: Subscriber,
+    _marker: PhantomData<fn() -> (T, C)>,
+}
+
+impl<T, C> TypedSubscriber<T, C> {
+    /// Wraps an existing subscriber.
+    pub fn new(subscriber: Subscriber) -> TypedSubscriber<T, C> {
+        TypedSubscriber {
+            subscriber,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the underlying subscriber.
+    pub fn into_inner(self) -> Subscriber {
+        self.subscriber
+    }
+}
+
+impl<T, C> fmt::Debug for TypedSubscriber<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedSubscriber").finish_non_exhaustive()
+    }
+}
+
+impl<T: DeserializeOwned, C: Codec> Stream for TypedSubscriber<T, C> {
+    type Item = TypedMessage<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.subscriber.poll_next_unpin(cx).map(|message| {
+            message.map(|message| TypedMessage {
+                value: message.decode::<C, T>(),
+                message,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        name: String,
+        count: u64,
+    }
+
+    fn event() -> Event {
+        Event {
+            name: "created".to_string(),
+            count: 3,
+        }
+    }
+
+    fn message<C: Codec>(headers: HeaderMap) -> Message {
+        Message {
+            subject: "events".into(),
+            reply: None,
+            payload: C::encode(&event()).unwrap(),
+            headers: Some(headers),
+            status: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn json() {
+        let (headers, _) = encode::<Json, _>(&event()).unwrap();
+        assert_eq!(headers.get(CONTENT_TYPE).unwrap(), "application/json");
+        assert_eq!(
+            message::<Json>(headers).decode::<Json, Event>().unwrap(),
+            event()
+        );
+    }
+
+    #[test]
+    fn missing_content_type() {
+        assert_eq!(
+            message::<Json>(HeaderMap::new())
+                .decode::<Json, Event>()
+                .unwrap(),
+            event()
+        );
+    }
+
+    #[test]
+    fn content_type_parameters() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("Application/JSON; charset=utf-8"),
+        );
+        assert_eq!(
+            message::<Json>(headers).decode::<Json, Event>().unwrap(),
+            event()
+        );
+    }
+
+    #[test]
+    fn content_type_mismatch() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        assert!(matches!(
+            message::<Json>(headers).decode::<Json, Event>(),
+            Err(CodecError::ContentType(content_type)) if content_type == "text/plain"
+        ));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack() {
+        let (headers, _) = encode::<MessagePack, _>(&event()).unwrap();
+        assert_eq!(
+            message::<MessagePack>(headers)
+                .decode::<MessagePack, Event>()
+                .unwrap(),
+            event()
+        );
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor() {
+        let (headers, _) = encode::<Cbor, _>(&event()).unwrap();
+        assert_eq!(
+            message::<Cbor>(headers).decode::<Cbor, Event>().unwrap(),
+            event()
+        );
+    }
+}
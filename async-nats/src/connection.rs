@@ -12,6 +12,8 @@
 // limitations under the License.
 
 use std::str::{self, FromStr};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use subslice::SubsliceExt;
 use tokio::io::{AsyncRead, AsyncWriteExt};
@@ -21,6 +23,7 @@ use bytes::{Buf, BytesMut};
 use tokio::io;
 
 use crate::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::statistics::Counters;
 use crate::status::StatusCode;
 use crate::{ClientOp, ServerError, ServerOp};
 
@@ -34,11 +37,23 @@ impl<T> AsyncReadWrite for T where T: AsyncRead + AsyncWrite + Unpin + Send {}
 pub(crate) struct Connection {
     pub(crate) stream: Box<dyn AsyncReadWrite>,
     pub(crate) buffer: BytesMut,
+    pub(crate) statistics: Arc<Counters>,
+    /// Payload bytes written since the last flush.
+    pending_bytes: u64,
 }
 
 /// Internal representation of the connection.
 /// Helds connection with NATS Server and communicates with `Client` via channels.
 impl Connection {
+    pub(crate) fn new(stream: Box<dyn AsyncReadWrite>, statistics: Arc<Counters>) -> Connection {
+        Connection {
+            stream,
+            buffer: BytesMut::new(),
+            statistics,
+            pending_bytes: 0,
+        }
+    }
+
     pub(crate) fn try_read_op(&mut self) -> Result<Option<ServerOp>, io::Error> {
         let maybe_len = self.buffer.find(b"\r\n");
         if maybe_len.is_none() {
@@ -203,7 +218,8 @@ impl Connection {
             let payload = self.buffer.split_to(num_bytes - num_header_bytes).freeze();
             self.buffer.advance(2);
 
-            let mut lines = std::str::from_utf8(&buffer).unwrap().lines().peekable();
+            let buffer = String::from_utf8_lossy(&buffer);
+            let mut lines = buffer.lines().peekable();
             let version_line = lines.next().ok_or_else(|| {
                 io::Error::new(io::ErrorKind::InvalidInput, "no header version line found")
             })?;
@@ -251,19 +267,30 @@ impl Connection {
                     continue;
                 }
 
-                let (key, value) = line.split_once(':').ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "no header version line found")
-                })?;
+                // a malformed header only affects this message, so it is skipped rather
+                // than failing the whole connection.
+                let (key, value) = match line.split_once(':') {
+                    Some(header) => header,
+                    None => {
+                        warn!("skipping malformed header line: {:?}", line);
+                        continue;
+                    }
+                };
 
                 let mut value = String::from_str(value).unwrap();
                 while let Some(v) = lines.next_if(|s| s.starts_with(char::is_whitespace)) {
                     value.push_str(v);
                 }
 
-                headers.append(
-                    HeaderName::from_str(key).unwrap(),
-                    HeaderValue::from_str(value.trim()).unwrap(),
-                );
+                match (
+                    HeaderName::from_str(key.trim()),
+                    HeaderValue::from_str(value.trim()),
+                ) {
+                    (Ok(key), Ok(value)) => headers.append(key, value),
+                    (Err(err), _) | (_, Err(err)) => {
+                        warn!("skipping invalid header {:?}: {}", key, err);
+                    }
+                }
             }
 
             return Ok(Some(ServerOp::Message {
@@ -291,6 +318,12 @@ impl Connection {
     pub(crate) async fn read_op(&mut self) -> Result<Option<ServerOp>, io::Error> {
         loop {
             if let Some(op) = self.try_read_op()? {
+                if let ServerOp::Message { payload, .. } = &op {
+                    self.statistics.in_messages.fetch_add(1, Ordering::Relaxed);
+                    self.statistics
+                        .in_bytes
+                        .fetch_add(payload.len() as u64, Ordering::Relaxed);
+                }
                 return Ok(Some(op));
             }
 
@@ -335,16 +368,7 @@ impl Connection {
                 }
 
                 if let Some(headers) = headers {
-                    let mut header = Vec::new();
-                    header.extend_from_slice(b"NATS/1.0\r\n");
-                    for (key, value) in headers.iter() {
-                        header.extend_from_slice(key.as_ref());
-                        header.push(b':');
-                        header.extend_from_slice(value.as_ref());
-                        header.extend_from_slice(b"\r\n");
-                    }
-
-                    header.extend_from_slice(b"\r\n");
+                    let header = headers.to_bytes();
 
                     let mut header_len_buf = itoa::Buffer::new();
                     self.stream
@@ -374,6 +398,14 @@ impl Connection {
 
                 self.stream.write_all(&payload).await?;
                 self.stream.write_all(b"\r\n").await?;
+
+                let len = payload.len() as u64;
+                self.pending_bytes += len;
+                self.statistics.out_messages.fetch_add(1, Ordering::Relaxed);
+                self.statistics.out_bytes.fetch_add(len, Ordering::Relaxed);
+                self.statistics
+                    .pending_bytes
+                    .fetch_add(len, Ordering::Relaxed);
             }
 
             ClientOp::Subscribe {
@@ -405,7 +437,7 @@ impl Connection {
             }
             ClientOp::Ping => {
                 self.stream.write_all(b"PING\r\n").await?;
-                self.stream.flush().await?;
+                self.flush().await?;
             }
             ClientOp::Pong => {
                 self.stream.write_all(b"PONG\r\n").await?;
@@ -416,7 +448,19 @@ impl Connection {
     }
 
     pub(crate) async fn flush(&mut self) -> Result<(), io::Error> {
-        self.stream.flush().await
+        self.stream.flush().await?;
+        self.discard_pending();
+
+        Ok(())
+    }
+
+    /// Stops counting the unflushed payload bytes as pending, either because they were
+    /// flushed or because they were lost with the connection.
+    pub(crate) fn discard_pending(&mut self) {
+        self.statistics
+            .pending_bytes
+            .fetch_sub(self.pending_bytes, Ordering::Relaxed);
+        self.pending_bytes = 0;
     }
 }
 
@@ -424,16 +468,12 @@ impl Connection {
 mod read_op {
     use super::Connection;
     use crate::{HeaderMap, ServerError, ServerInfo, ServerOp, StatusCode};
-    use bytes::BytesMut;
     use tokio::io::{self, AsyncWriteExt};
 
     #[tokio::test]
     async fn ok() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"+OK\r\n").await.unwrap();
         let result = connection.read_op().await.unwrap();
@@ -443,10 +483,7 @@ mod read_op {
     #[tokio::test]
     async fn ping() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"PING\r\n").await.unwrap();
         let result = connection.read_op().await.unwrap();
@@ -456,10 +493,7 @@ mod read_op {
     #[tokio::test]
     async fn pong() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"PONG\r\n").await.unwrap();
         let result = connection.read_op().await.unwrap();
@@ -469,10 +503,7 @@ mod read_op {
     #[tokio::test]
     async fn info() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"INFO {}\r\n").await.unwrap();
         server.flush().await.unwrap();
@@ -502,10 +533,7 @@ mod read_op {
     #[tokio::test]
     async fn error() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"INFO {}\r\n").await.unwrap();
         let result = connection.read_op().await.unwrap();
@@ -530,10 +558,7 @@ mod read_op {
     #[tokio::test]
     async fn message() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server
             .write_all(b"MSG FOO.BAR 9 11\r\nHello World\r\n")
@@ -650,6 +675,35 @@ mod read_op {
             })
         );
 
+        // invalid headers are skipped, the message is still delivered.
+        server
+            .write_all(b"HMSG FOO.BAR 10 43 45\r\n")
+            .await
+            .unwrap();
+        server.write_all(b"NATS/1.0\r\n").await.unwrap();
+        server.write_all(b"Bad Name: x\r\n").await.unwrap();
+        server.write_all(b"NoColon\r\n").await.unwrap();
+        server.write_all(b"Good: y\r\n").await.unwrap();
+        server.write_all(b"\r\n").await.unwrap();
+        server.write_all(b"Hi\r\n").await.unwrap();
+
+        let result = connection.read_op().await.unwrap();
+        assert_eq!(
+            result,
+            Some(ServerOp::Message {
+                sid: 10,
+                subject: "FOO.BAR".into(),
+                reply: None,
+                headers: Some(HeaderMap::from_iter([(
+                    "Good".parse().unwrap(),
+                    "y".parse().unwrap()
+                )])),
+                payload: "Hi".into(),
+                status: None,
+                description: None,
+            })
+        );
+
         server
             .write_all(b"MSG FOO.BAR 9 11\r\nHello Again\r\n")
             .await
@@ -673,10 +727,7 @@ mod read_op {
     #[tokio::test]
     async fn unknown() {
         let (stream, mut server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         server.write_all(b"ONE\r\n").await.unwrap();
         connection.read_op().await.unwrap_err();
@@ -727,16 +778,12 @@ mod read_op {
 mod write_op {
     use super::Connection;
     use crate::{ClientOp, ConnectInfo, HeaderMap, Protocol};
-    use bytes::BytesMut;
     use tokio::io::{self, AsyncBufReadExt, BufReader};
 
     #[tokio::test]
     async fn publish() {
         let (stream, server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         connection
             .write_op(ClientOp::Publish {
@@ -792,17 +839,14 @@ mod write_op {
         reader.read_line(&mut buffer).await.unwrap();
         assert_eq!(
             buffer,
-            "HPUB FOO.BAR INBOX.67 22 33\r\nNATS/1.0\r\nheader:X\r\n\r\n"
+            "HPUB FOO.BAR INBOX.67 22 33\r\nNATS/1.0\r\nHeader:X\r\n\r\n"
         );
     }
 
     #[tokio::test]
     async fn subscribe() {
         let (stream, server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         connection
             .write_op(ClientOp::Subscribe {
@@ -837,10 +881,7 @@ mod write_op {
     #[tokio::test]
     async fn unsubscribe() {
         let (stream, server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         connection
             .write_op(ClientOp::Unsubscribe { sid: 11, max: None })
@@ -870,10 +911,7 @@ mod write_op {
     #[tokio::test]
     async fn ping() {
         let (stream, server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         let mut reader = BufReader::new(server);
         let mut buffer = String::new();
@@ -889,10 +927,7 @@ mod write_op {
     #[tokio::test]
     async fn pong() {
         let (stream, server) = io::duplex(128);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         let mut reader = BufReader::new(server);
         let mut buffer = String::new();
@@ -908,10 +943,7 @@ mod write_op {
     #[tokio::test]
     async fn connect() {
         let (stream, server) = io::duplex(1024);
-        let mut connection = Connection {
-            stream: Box::new(stream),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(Box::new(stream), Default::default());
 
         let mut reader = BufReader::new(server);
         let mut buffer = String::new();
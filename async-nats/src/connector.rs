@@ -1,4 +1,6 @@
 use crate::connection::Connection;
+use crate::options::ReconnectDelayCallback;
+use crate::statistics::Counters;
 use crate::tls;
 use crate::Authorization;
 use crate::ClientError;
@@ -13,13 +15,10 @@ use crate::SocketAddr;
 use crate::ToServerAddrs;
 use crate::LANG;
 use crate::VERSION;
-use bytes::BytesMut;
-use std::cmp;
-use std::collections::HashMap;
+use rand::seq::SliceRandom;
 use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::io::BufWriter;
 use tokio::io::ErrorKind;
 use tokio::net::TcpStream;
@@ -34,14 +33,23 @@ pub(crate) struct ConnectorOptions {
     pub(crate) tls_client_config: Option<rustls::ClientConfig>,
     pub(crate) auth: Authorization,
     pub(crate) no_echo: bool,
+    pub(crate) max_reconnects: Option<usize>,
+    pub(crate) reconnect_delay_callback: ReconnectDelayCallback,
+    pub(crate) retain_servers_order: bool,
+    pub(crate) ignore_discovered_servers: bool,
 }
 
 /// Maintains a list of servers and establishes connections.
 pub(crate) struct Connector {
-    /// A map of servers and number of connect attempts.
-    servers: HashMap<ServerAddr, usize>,
+    /// A list of servers and number of connect attempts.
+    servers: Vec<(ServerAddr, usize)>,
+    /// The server of the current connection.
+    current: Option<ServerAddr>,
+    /// A server in lame duck mode, tried only after all other servers.
+    lame_duck: Option<ServerAddr>,
     options: ConnectorOptions,
     events_tx: tokio::sync::mpsc::Sender<Event>,
+    statistics: Arc<Counters>,
 }
 
 impl Connector {
@@ -49,6 +57,7 @@ impl Connector {
         addrs: A,
         options: ConnectorOptions,
         events_tx: tokio::sync::mpsc::Sender<Event>,
+        statistics: Arc<Counters>,
     ) -> Result<Connector, io::Error> {
         let servers = addrs
             .to_server_addrs()?
@@ -58,57 +67,114 @@ impl Connector {
 
         Ok(Connector {
             servers,
+            current: None,
+            lame_duck: None,
             options,
             events_tx,
+            statistics,
         })
     }
 
+    /// Tries to connect until it succeeds or [ConnectorOptions::max_reconnects] attempts
+    /// have failed.
     pub(crate) async fn connect(&mut self) -> Result<(ServerInfo, Connection), io::Error> {
+        let mut attempts = 0;
         loop {
             match self.try_connect().await {
                 Ok(inner) => return Ok(inner),
                 Err(error) => {
+                    attempts += 1;
                     self.events_tx
                         .send(Event::ClientError(ClientError::Other(error.to_string())))
                         .await
                         .ok();
+
+                    if let Some(max_reconnects) = self.options.max_reconnects {
+                        if attempts >= max_reconnects {
+                            return Err(io::Error::new(
+                                ErrorKind::NotConnected,
+                                format!("failed to connect after {} attempts: {}", attempts, error),
+                            ));
+                        }
+                    }
                 }
             }
         }
     }
 
+    /// Adds the servers advertised by the cluster to the list of known servers, unless
+    /// [ConnectorOptions::ignore_discovered_servers] is set.
+    pub(crate) fn add_discovered_servers(&mut self, urls: &[String]) {
+        if self.options.ignore_discovered_servers {
+            return;
+        }
+
+        for url in urls {
+            match url.parse::<ServerAddr>() {
+                Ok(server_addr) => {
+                    if !self.servers.iter().any(|(addr, _)| *addr == server_addr) {
+                        self.servers.push((server_addr, 0));
+                    }
+                }
+                Err(err) => warn!("ignoring invalid server url {}: {}", url, err),
+            }
+        }
+    }
+
+    /// Marks the current server as being in lame duck mode, so that it is tried last on
+    /// the next connect. Returns false if there is no other server to migrate to.
+    pub(crate) fn lame_duck(&mut self) -> bool {
+        if self.servers.len() < 2 {
+            return false;
+        }
+        self.lame_duck = self.current.clone();
+
+        true
+    }
+
+    fn server_attempts(&mut self, server_addr: &ServerAddr) -> &mut usize {
+        self.servers
+            .iter_mut()
+            .find(|(addr, _)| addr == server_addr)
+            .map(|(_, attempts)| attempts)
+            .unwrap()
+    }
+
     pub(crate) async fn try_connect(&mut self) -> Result<(ServerInfo, Connection), io::Error> {
         let mut error = None;
 
-        let server_addrs: Vec<ServerAddr> = self.servers.keys().cloned().collect();
+        let mut server_addrs: Vec<ServerAddr> =
+            self.servers.iter().map(|(addr, _)| addr.clone()).collect();
+        if !self.options.retain_servers_order {
+            server_addrs.shuffle(&mut rand::thread_rng());
+        }
+        if let Some(lame_duck) = self.lame_duck.take() {
+            server_addrs.retain(|addr| *addr != lame_duck);
+            server_addrs.push(lame_duck);
+        }
+
         for server_addr in server_addrs {
-            let server_attempts = self.servers.get_mut(&server_addr).unwrap();
-            let duration = if *server_attempts == 0 {
-                Duration::from_millis(0)
-            } else {
-                let exp: u32 = (*server_attempts - 1).try_into().unwrap_or(std::u32::MAX);
-                let max = Duration::from_secs(4);
+            let server_attempts = self.server_attempts(&server_addr);
+            let attempts = *server_attempts;
+            *server_attempts += 1;
 
-                cmp::min(Duration::from_millis(2_u64.saturating_pow(exp)), max)
-            };
+            sleep(self.options.reconnect_delay_callback.call(attempts)).await;
 
-            *server_attempts += 1;
-            sleep(duration).await;
+            let mut socket_addrs: Vec<SocketAddr> = server_addr.socket_addrs()?.collect();
+            if !self.options.retain_servers_order {
+                socket_addrs.shuffle(&mut rand::thread_rng());
+            }
 
-            let socket_addrs = server_addr.socket_addrs()?;
             for socket_addr in socket_addrs {
                 match self
                     .try_connect_to(&socket_addr, server_addr.tls_required(), server_addr.host())
                     .await
                 {
                     Ok((server_info, mut connection)) => {
-                        for url in &server_info.connect_urls {
-                            let server_addr = url.parse::<ServerAddr>()?;
-                            self.servers.entry(server_addr).or_insert(0);
-                        }
+                        self.add_discovered_servers(&server_info.connect_urls);
 
-                        let server_attempts = self.servers.get_mut(&server_addr).unwrap();
-                        *server_attempts = 0;
+                        *self.server_attempts(&server_addr) = 0;
+                        self.current = Some(server_addr.clone());
 
                         let tls_required = self.options.tls_required || server_addr.tls_required();
                         let mut connect_info = ConnectInfo {
@@ -220,6 +286,10 @@ impl Connector {
         Err(error.unwrap())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "connect", skip_all, fields(addr = %socket_addr, server_id))
+    )]
     pub(crate) async fn try_connect_to(
         &self,
         socket_addr: &SocketAddr,
@@ -231,14 +301,17 @@ impl Connector {
         let tcp_stream = TcpStream::connect(socket_addr).await?;
         tcp_stream.set_nodelay(true)?;
 
-        let mut connection = Connection {
-            stream: Box::new(BufWriter::new(tcp_stream)),
-            buffer: BytesMut::new(),
-        };
+        let mut connection = Connection::new(
+            Box::new(BufWriter::new(tcp_stream)),
+            self.statistics.clone(),
+        );
 
         let op = connection.read_op().await?;
         let info = match op {
-            Some(ServerOp::Info(info)) => info,
+            Some(ServerOp::Info(info)) => {
+                record!("server_id", info.server_id.as_str());
+                info
+            }
             Some(op) => {
                 return Err(io::Error::new(
                     ErrorKind::Other,
@@ -279,10 +352,10 @@ impl Connector {
                 ));
             };
 
-            connection = Connection {
-                stream: Box::new(tls_connector.connect(domain, connection.stream).await?),
-                buffer: BytesMut::new(),
-            };
+            connection = Connection::new(
+                Box::new(tls_connector.connect(domain, connection.stream).await?),
+                self.statistics.clone(),
+            );
         };
 
         Ok((*info, connection))
@@ -0,0 +1,151 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Command, DrainError, Message, Subscriber, UnsubscribeError};
+use futures::{Future, StreamExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+impl Subscriber {
+    /// Handles messages with the given closure in a spawned task, one message at a time.
+    /// The returned [Handler] can be used to unsubscribe or drain the subscription.
+    /// Dropping the [Handler] does not unsubscribe.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let handler = client
+    ///     .subscribe("foo".into())
+    ///     .await?
+    ///     .with_handler(|message| async move {
+    ///         println!("received {:?}", message);
+    ///     });
+    /// # handler.unsubscribe().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_handler<F, Fut>(self, handler: F) -> Handler
+    where
+        F: FnMut(Message) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.with_concurrent_handler(1, handler)
+    }
+
+    /// Handles messages with the given closure in a spawned task, running up to `limit`
+    /// invocations concurrently. Messages may be handled out of order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let handler = client
+    ///     .subscribe("requests".into())
+    ///     .await?
+    ///     .with_concurrent_handler(16, move |message| {
+    ///         let client = client.clone();
+    ///         async move {
+    ///             if let Some(reply) = message.reply {
+    ///                 client.publish(reply, "response".into()).await.ok();
+    ///             }
+    ///         }
+    ///     });
+    /// # handler.drain().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_concurrent_handler<F, Fut>(self, limit: usize, handler: F) -> Handler
+    where
+        F: FnMut(Message) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let sid = self.sid;
+        let sender = self.sender.clone();
+        let task = tokio::spawn(self.for_each_concurrent(limit, handler));
+
+        Handler { sid, sender, task }
+    }
+}
+
+/// A subscription handled by a closure, created with [Subscriber::with_handler] or
+/// [Client::subscribe_with_handler][crate::Client::subscribe_with_handler].
+#[derive(Debug)]
+pub struct Handler {
+    sid: u64,
+    sender: mpsc::Sender<Command>,
+    task: JoinHandle<()>,
+}
+
+impl Handler {
+    /// Unsubscribes and stops handling messages, including the ones already received.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let handler = client
+    ///     .subscribe_with_handler("foo".into(), |message| async move {
+    ///         println!("received {:?}", message);
+    ///     })
+    ///     .await?;
+    /// handler.unsubscribe().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn unsubscribe(self) -> Result<(), UnsubscribeError> {
+        if self.sender.is_closed() {
+            return Err(UnsubscribeError::new());
+        }
+        // the subscriber is dropped with the aborted task, which sends the `UNSUB`.
+        self.task.abort();
+        Ok(())
+    }
+
+    /// Unsubscribes and waits until all messages the server sent before processing the
+    /// unsubscribe were handled. A panic in the closure is resumed here.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let handler = client
+    ///     .subscribe_with_handler("foo".into(), |message| async move {
+    ///         println!("received {:?}", message);
+    ///     })
+    ///     .await?;
+    /// handler.drain().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn drain(self) -> Result<(), DrainError> {
+        self.sender
+            .send(Command::Drain { sid: self.sid })
+            .await
+            .map_err(|_| DrainError::new())?;
+        if let Err(err) = self.task.await {
+            if err.is_panic() {
+                std::panic::resume_unwind(err.into_panic());
+            }
+        }
+        Ok(())
+    }
+}
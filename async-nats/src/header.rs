@@ -11,7 +11,582 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! NATS [Message][crate::Message] headers, leveraging [http::header] crate.
-pub use http::header::{HeaderMap, HeaderName, HeaderValue};
+//! NATS [Message][crate::Message] headers.
+//!
+//! Header names are sent exactly as they were given, while lookups ignore ASCII case.
+//! Names and their values are kept in insertion order.
+//!
+//! # Examples
+//!
+//! ```
+//! use async_nats::HeaderMap;
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert("X-Trace", "abc".parse().unwrap());
+//! headers.set_msg_id("order-42");
+//! headers.set_expected_last_sequence(10);
+//!
+//! assert_eq!(headers.get("x-trace").unwrap(), "abc");
+//! assert_eq!(headers.msg_id(), Some("order-42"));
+//! assert_eq!(headers.expected_last_sequence(), Some(10));
+//! ```
 
-pub const NATS_LAST_STREAM: &str = "nats-last-stream";
+use std::error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+/// Deduplicates published messages within the stream duplicate window.
+pub const NATS_MSG_ID: &str = "Nats-Msg-Id";
+/// Fails the publish unless the subject belongs to the given stream.
+pub const NATS_EXPECTED_STREAM: &str = "Nats-Expected-Stream";
+/// Fails the publish unless the last message in the stream had the given id.
+pub const NATS_EXPECTED_LAST_MSG_ID: &str = "Nats-Expected-Last-Msg-Id";
+/// Fails the publish unless the last message in the stream had the given sequence.
+pub const NATS_EXPECTED_LAST_SEQUENCE: &str = "Nats-Expected-Last-Sequence";
+/// Fails the publish unless the last message on the subject had the given sequence.
+pub const NATS_EXPECTED_LAST_SUBJECT_SEQUENCE: &str = "Nats-Expected-Last-Subject-Sequence";
+/// Purges the previous messages of the subject (`sub`) or of the stream (`all`).
+pub const NATS_ROLLUP: &str = "Nats-Rollup";
+/// Stream of a message returned by a direct get or republished by a stream.
+pub const NATS_STREAM: &str = "Nats-Stream";
+/// Stream sequence of a message returned by a direct get or republished by a stream.
+pub const NATS_SEQUENCE: &str = "Nats-Sequence";
+/// Original subject of a message returned by a direct get.
+pub const NATS_SUBJECT: &str = "Nats-Subject";
+/// Time, in RFC 3339 format, a message returned by a direct get was stored.
+pub const NATS_TIME_STAMP: &str = "Nats-Time-Stamp";
+/// Last stream sequence delivered to a consumer, sent with idle heartbeats.
+pub const NATS_LAST_STREAM: &str = "Nats-Last-Stream";
+/// Last consumer sequence delivered to a consumer, sent with idle heartbeats.
+pub const NATS_LAST_CONSUMER: &str = "Nats-Last-Consumer";
+
+/// An error returned when parsing an invalid [HeaderName] or [HeaderValue].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHeaderError(&'static str);
+
+impl fmt::Display for ParseHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for ParseHeaderError {}
+
+/// A case preserving header name, compared ignoring ASCII case.
+/// Can contain printable ASCII characters except `:`.
+#[derive(Clone)]
+pub struct HeaderName {
+    inner: String,
+}
+
+impl HeaderName {
+    /// Creates the header name from a static string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not valid.
+    pub fn from_static(name: &'static str) -> HeaderName {
+        name.parse().expect("invalid header name")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl FromStr for HeaderName {
+    type Err = ParseHeaderError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if name.is_empty() {
+            return Err(ParseHeaderError("empty header name"));
+        }
+        if !name.bytes().all(|c| c.is_ascii_graphic() && c != b':') {
+            return Err(ParseHeaderError("invalid character in header name"));
+        }
+
+        Ok(HeaderName {
+            inner: name.to_string(),
+        })
+    }
+}
+
+impl PartialEq for HeaderName {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.eq_ignore_ascii_case(&other.inner)
+    }
+}
+
+impl Eq for HeaderName {}
+
+impl Hash for HeaderName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.inner.bytes() {
+            state.write_u8(c.to_ascii_lowercase());
+        }
+    }
+}
+
+impl PartialEq<str> for HeaderName {
+    fn eq(&self, other: &str) -> bool {
+        self.inner.eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for HeaderName {
+    fn eq(&self, other: &&str) -> bool {
+        self.inner.eq_ignore_ascii_case(other)
+    }
+}
+
+impl fmt::Debug for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl fmt::Display for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl AsRef<str> for HeaderName {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for HeaderName {
+    fn as_ref(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+}
+
+/// A header value. Can contain any characters except CR and LF.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct HeaderValue {
+    inner: String,
+}
+
+impl HeaderValue {
+    /// Creates the header value from a static string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not valid.
+    pub fn from_static(value: &'static str) -> HeaderValue {
+        value.parse().expect("invalid header value")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl FromStr for HeaderValue {
+    type Err = ParseHeaderError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.contains(['\r', '\n']) {
+            return Err(ParseHeaderError("header value contains a line break"));
+        }
+
+        Ok(HeaderValue {
+            inner: value.to_string(),
+        })
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for HeaderValue {
+                fn from(value: $t) -> Self {
+                    HeaderValue {
+                        inner: value.to_string(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(u16, u32, u64, usize, i16, i32, i64, isize);
+
+impl PartialEq<str> for HeaderValue {
+    fn eq(&self, other: &str) -> bool {
+        self.inner == other
+    }
+}
+
+impl PartialEq<&str> for HeaderValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.inner == *other
+    }
+}
+
+impl fmt::Debug for HeaderValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl fmt::Display for HeaderValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl AsRef<str> for HeaderValue {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for HeaderValue {
+    fn as_ref(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+}
+
+/// Types which can be used as a [HeaderName] when inserting into a [HeaderMap].
+pub trait IntoHeaderName {
+    fn into_header_name(self) -> HeaderName;
+}
+
+impl IntoHeaderName for HeaderName {
+    fn into_header_name(self) -> HeaderName {
+        self
+    }
+}
+
+/// Panics if the name is not valid, like [HeaderName::from_static].
+impl IntoHeaderName for &'static str {
+    fn into_header_name(self) -> HeaderName {
+        HeaderName::from_static(self)
+    }
+}
+
+/// A multi-map of [HeaderName] to [HeaderValues][HeaderValue], with typed accessors for the
+/// headers used by JetStream.
+///
+/// Headers are few, so they are kept in a list in insertion order rather than hashed.
+/// Equality does not depend on the order of the names.
+#[derive(Debug, Clone, Default)]
+pub struct HeaderMap {
+    inner: Vec<(HeaderName, Vec<HeaderValue>)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> HeaderMap {
+        HeaderMap::default()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.inner.iter().position(|(key, _)| key == name)
+    }
+
+    /// Sets the header to a single value, returning the first of the previous values.
+    /// A header which was already set keeps its position and name.
+    pub fn insert<K: IntoHeaderName>(
+        &mut self,
+        name: K,
+        value: HeaderValue,
+    ) -> Option<HeaderValue> {
+        let name = name.into_header_name();
+        match self.position(name.as_str()) {
+            Some(index) => std::mem::replace(&mut self.inner[index].1, vec![value])
+                .into_iter()
+                .next(),
+            None => {
+                self.inner.push((name, vec![value]));
+                None
+            }
+        }
+    }
+
+    /// Adds a value to the header, keeping the previous values.
+    pub fn append<K: IntoHeaderName>(&mut self, name: K, value: HeaderValue) {
+        let name = name.into_header_name();
+        match self.position(name.as_str()) {
+            Some(index) => self.inner[index].1.push(value),
+            None => self.inner.push((name, vec![value])),
+        }
+    }
+
+    /// Returns the first value of the header.
+    pub fn get<K: AsRef<str>>(&self, name: K) -> Option<&HeaderValue> {
+        self.get_all(name).next()
+    }
+
+    /// Returns all values of the header.
+    pub fn get_all<K: AsRef<str>>(&self, name: K) -> impl Iterator<Item = &HeaderValue> {
+        self.position(name.as_ref())
+            .map(|index| &self.inner[index].1)
+            .into_iter()
+            .flatten()
+    }
+
+    /// Removes the header, returning its first value.
+    pub fn remove<K: AsRef<str>>(&mut self, name: K) -> Option<HeaderValue> {
+        let index = self.position(name.as_ref())?;
+        self.inner.remove(index).1.into_iter().next()
+    }
+
+    pub fn contains_key<K: AsRef<str>>(&self, name: K) -> bool {
+        self.position(name.as_ref()).is_some()
+    }
+
+    /// Returns all name and value pairs, with a name repeated for each of its values.
+    pub fn iter(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
+        self.inner
+            .iter()
+            .flat_map(|(name, values)| values.iter().map(move |value| (name, value)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &HeaderName> {
+        self.inner.iter().map(|(name, _)| name)
+    }
+
+    /// Returns the number of values, counting each value of a name.
+    pub fn len(&self) -> usize {
+        self.inner.iter().map(|(_, values)| values.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).map(HeaderValue::as_str)
+    }
+
+    fn get_u64(&self, name: &str) -> Option<u64> {
+        self.get_str(name)?.trim().parse().ok()
+    }
+
+    fn set_str(&mut self, name: &'static str, value: &str) {
+        // strip line breaks rather than failing, as they are never valid in these headers.
+        let value = value.replace(['\r', '\n'], "");
+        self.insert(name, HeaderValue { inner: value });
+    }
+
+    /// Returns the [NATS_MSG_ID] header.
+    pub fn msg_id(&self) -> Option<&str> {
+        self.get_str(NATS_MSG_ID)
+    }
+
+    /// Sets the [NATS_MSG_ID] header.
+    pub fn set_msg_id(&mut self, id: &str) {
+        self.set_str(NATS_MSG_ID, id);
+    }
+
+    /// Returns the [NATS_EXPECTED_STREAM] header.
+    pub fn expected_stream(&self) -> Option<&str> {
+        self.get_str(NATS_EXPECTED_STREAM)
+    }
+
+    /// Sets the [NATS_EXPECTED_STREAM] header.
+    pub fn set_expected_stream(&mut self, stream: &str) {
+        self.set_str(NATS_EXPECTED_STREAM, stream);
+    }
+
+    /// Returns the [NATS_EXPECTED_LAST_MSG_ID] header.
+    pub fn expected_last_msg_id(&self) -> Option<&str> {
+        self.get_str(NATS_EXPECTED_LAST_MSG_ID)
+    }
+
+    /// Sets the [NATS_EXPECTED_LAST_MSG_ID] header.
+    pub fn set_expected_last_msg_id(&mut self, id: &str) {
+        self.set_str(NATS_EXPECTED_LAST_MSG_ID, id);
+    }
+
+    /// Returns the [NATS_EXPECTED_LAST_SEQUENCE] header, if it is a valid sequence.
+    pub fn expected_last_sequence(&self) -> Option<u64> {
+        self.get_u64(NATS_EXPECTED_LAST_SEQUENCE)
+    }
+
+    /// Sets the [NATS_EXPECTED_LAST_SEQUENCE] header.
+    pub fn set_expected_last_sequence(&mut self, sequence: u64) {
+        self.insert(NATS_EXPECTED_LAST_SEQUENCE, sequence.into());
+    }
+
+    /// Returns the [NATS_EXPECTED_LAST_SUBJECT_SEQUENCE] header, if it is a valid sequence.
+    pub fn expected_last_subject_sequence(&self) -> Option<u64> {
+        self.get_u64(NATS_EXPECTED_LAST_SUBJECT_SEQUENCE)
+    }
+
+    /// Sets the [NATS_EXPECTED_LAST_SUBJECT_SEQUENCE] header.
+    pub fn set_expected_last_subject_sequence(&mut self, sequence: u64) {
+        self.insert(NATS_EXPECTED_LAST_SUBJECT_SEQUENCE, sequence.into());
+    }
+
+    /// Returns the [NATS_ROLLUP] header.
+    pub fn rollup(&self) -> Option<&str> {
+        self.get_str(NATS_ROLLUP)
+    }
+
+    /// Sets the [NATS_ROLLUP] header, either to `sub` or `all`.
+    pub fn set_rollup(&mut self, rollup: &str) {
+        self.set_str(NATS_ROLLUP, rollup);
+    }
+
+    /// Returns the [NATS_STREAM] header.
+    pub fn stream(&self) -> Option<&str> {
+        self.get_str(NATS_STREAM)
+    }
+
+    /// Returns the [NATS_SEQUENCE] header, if it is a valid sequence.
+    pub fn sequence(&self) -> Option<u64> {
+        self.get_u64(NATS_SEQUENCE)
+    }
+
+    /// Returns the [NATS_SUBJECT] header.
+    pub fn subject(&self) -> Option<&str> {
+        self.get_str(NATS_SUBJECT)
+    }
+
+    /// Returns the [NATS_TIME_STAMP] header.
+    pub fn time_stamp(&self) -> Option<&str> {
+        self.get_str(NATS_TIME_STAMP)
+    }
+
+    /// Encodes the headers in the wire format, including the version line.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(b"NATS/1.0\r\n");
+        for (key, value) in self.iter() {
+            header.extend_from_slice(key.as_ref());
+            header.push(b':');
+            header.extend_from_slice(value.as_ref());
+            header.extend_from_slice(b"\r\n");
+        }
+        header.extend_from_slice(b"\r\n");
+        header
+    }
+}
+
+impl PartialEq for HeaderMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.len() == other.inner.len()
+            && self
+                .inner
+                .iter()
+                .all(|(name, values)| other.get_all(name).eq(values.iter()))
+    }
+}
+
+impl Eq for HeaderMap {}
+
+impl FromIterator<(HeaderName, HeaderValue)> for HeaderMap {
+    fn from_iter<T: IntoIterator<Item = (HeaderName, HeaderValue)>>(iter: T) -> Self {
+        let mut headers = HeaderMap::new();
+        for (name, value) in iter {
+            headers.append(name, value);
+        }
+        headers
+    }
+}
+
+impl Extend<(HeaderName, HeaderValue)> for HeaderMap {
+    fn extend<T: IntoIterator<Item = (HeaderName, HeaderValue)>>(&mut self, iter: T) {
+        for (name, value) in iter {
+            self.append(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_preserving() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Custom-Header", "value".parse().unwrap());
+
+        assert_eq!(headers.get("x-custom-header").unwrap(), "value");
+        assert_eq!(headers.keys().next().unwrap().as_str(), "X-Custom-Header");
+    }
+
+    #[test]
+    fn multiple_values() {
+        let mut headers = HeaderMap::new();
+        headers.append("Key", "one".parse().unwrap());
+        headers.append("KEY", "two".parse().unwrap());
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers.get("key").unwrap(), "one");
+        assert_eq!(
+            headers.get_all("key").collect::<Vec<_>>(),
+            vec!["one", "two"]
+        );
+
+        assert_eq!(
+            headers.insert("key", "three".parse().unwrap()).unwrap(),
+            "one"
+        );
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn invalid() {
+        "".parse::<HeaderName>().unwrap_err();
+        "Key:".parse::<HeaderName>().unwrap_err();
+        "Key Name".parse::<HeaderName>().unwrap_err();
+        "value\r\nPUB".parse::<HeaderValue>().unwrap_err();
+        // NATS allows characters which HTTP header names don't.
+        "Nats/Key.{1}".parse::<HeaderName>().unwrap();
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let mut headers = HeaderMap::new();
+        headers.set_expected_stream("events");
+        headers.set_expected_last_subject_sequence(5);
+        headers.set_rollup("sub");
+        headers.insert(NATS_SEQUENCE, "not a number".parse().unwrap());
+
+        assert_eq!(headers.expected_stream(), Some("events"));
+        assert_eq!(headers.expected_last_subject_sequence(), Some(5));
+        assert_eq!(headers.rollup(), Some("sub"));
+        assert_eq!(headers.sequence(), None);
+        assert_eq!(headers.msg_id(), None);
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut headers = HeaderMap::new();
+        for name in ["Zeta", "Alpha", "Mu", "Beta"] {
+            headers.insert(name, "value".parse().unwrap());
+        }
+        headers.insert("alpha", "other".parse().unwrap());
+
+        assert_eq!(
+            headers.keys().map(HeaderName::as_str).collect::<Vec<_>>(),
+            vec!["Zeta", "Alpha", "Mu", "Beta"]
+        );
+
+        let mut pairs: Vec<_> = headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        pairs.reverse();
+        let reversed: HeaderMap = pairs.into_iter().collect();
+        assert_eq!(headers, reversed);
+    }
+
+    #[test]
+    fn to_bytes() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Key", "value".parse().unwrap());
+        assert_eq!(headers.to_bytes(), b"NATS/1.0\r\nX-Key:value\r\n\r\n");
+    }
+}
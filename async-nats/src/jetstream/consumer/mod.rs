@@ -15,7 +15,7 @@
 
 pub mod pull;
 pub mod push;
-use std::io::ErrorKind;
+use std::fmt;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -24,9 +24,9 @@ use time::serde::rfc3339;
 
 use super::response::Response;
 use super::stream::ClusterInfo;
-use super::Context;
+use super::{Context, RequestError};
 use crate::jetstream::consumer;
-use crate::Error;
+use crate::{PublishError, StatusCode, SubscribeError};
 
 pub trait IntoConsumerConfig {
     fn into_consumer_config(self) -> Config;
@@ -70,7 +70,7 @@ impl<T: IntoConsumerConfig> Consumer<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn info(&mut self) -> Result<&consumer::Info, Error> {
+    pub async fn info(&mut self) -> Result<&consumer::Info, RequestError> {
         let subject = format!("CONSUMER.INFO.{}.{}", self.info.stream_name, self.info.name);
 
         match self.context.request(subject, &json!({})).await? {
@@ -78,13 +78,7 @@ impl<T: IntoConsumerConfig> Consumer<T> {
                 self.info = info;
                 Ok(&self.info)
             }
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting consumer info: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
         }
     }
 
@@ -119,11 +113,92 @@ impl<T: IntoConsumerConfig> Consumer<T> {
 /// [Push][crate::jetstream::consumer::push::Config] config. It validates if given config is
 /// a valid target one.
 pub trait FromConsumer {
-    fn try_from_consumer_config(config: crate::jetstream::consumer::Config) -> Result<Self, Error>
+    fn try_from_consumer_config(
+        config: crate::jetstream::consumer::Config,
+    ) -> Result<Self, RequestError>
     where
         Self: Sized;
 }
 
+/// An error returned while receiving messages from a [Consumer], either by the message
+/// streams and batches of a pull consumer or by the push consumer message streams.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum StreamError {
+    /// Subscribing to the delivery subject or batch inbox failed.
+    Subscribe(SubscribeError),
+    /// Requesting the next batch of messages failed.
+    Publish(PublishError),
+    /// The batch request could not be serialized.
+    Serialization(serde_json::Error),
+    /// Recreating the ordered consumer after a gap in delivered sequences failed.
+    Recreate(RequestError),
+    /// The server responded with an unexpected status, e.g. when the consumer was deleted.
+    Status {
+        status: StatusCode,
+        description: Option<String>,
+    },
+    /// A received message could not be processed, e.g. because of malformed headers or reply
+    /// subject.
+    InvalidMessage(String),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Subscribe(err) => write!(f, "nats: consumer subscription failed: {}", err),
+            StreamError::Publish(err) => write!(f, "nats: requesting messages failed: {}", err),
+            StreamError::Serialization(err) => {
+                write!(f, "nats: failed to serialize batch request: {}", err)
+            }
+            StreamError::Recreate(err) => {
+                write!(f, "nats: failed to recreate ordered consumer: {}", err)
+            }
+            StreamError::Status {
+                status,
+                description,
+            } => write!(
+                f,
+                "nats: error while processing messages from the stream: {}, {:?}",
+                status, description
+            ),
+            StreamError::InvalidMessage(description) => {
+                write!(f, "nats: invalid consumer message: {}", description)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Subscribe(err) => Some(err),
+            StreamError::Publish(err) => Some(err),
+            StreamError::Serialization(err) => Some(err),
+            StreamError::Recreate(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SubscribeError> for StreamError {
+    fn from(err: SubscribeError) -> Self {
+        StreamError::Subscribe(err)
+    }
+}
+
+impl From<PublishError> for StreamError {
+    fn from(err: PublishError) -> Self {
+        StreamError::Publish(err)
+    }
+}
+
+impl From<serde_json::Error> for StreamError {
+    fn from(err: serde_json::Error) -> Self {
+        StreamError::Serialization(err)
+    }
+}
+
 pub type PullConsumer = Consumer<self::pull::Config>;
 pub type PushConsumer = Consumer<self::push::Config>;
 pub type OrderedPushConsumer = Consumer<self::push::OrderedConfig>;
@@ -313,7 +388,7 @@ impl IntoConsumerConfig for &Config {
 }
 
 impl FromConsumer for Config {
-    fn try_from_consumer_config(config: Config) -> Result<Self, Error>
+    fn try_from_consumer_config(config: Config) -> Result<Self, RequestError>
     where
         Self: Sized,
     {
@@ -18,11 +18,13 @@ use std::{task::Poll, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    jetstream::{self, Context},
-    Error, StatusCode, Subscriber,
+    jetstream::{self, Context, RequestError},
+    StatusCode, Subscriber,
 };
 
-use super::{AckPolicy, Consumer, DeliverPolicy, FromConsumer, IntoConsumerConfig, ReplayPolicy};
+use super::{
+    AckPolicy, Consumer, DeliverPolicy, FromConsumer, IntoConsumerConfig, ReplayPolicy, StreamError,
+};
 use jetstream::consumer;
 
 impl Consumer<Config> {
@@ -60,7 +62,7 @@ impl Consumer<Config> {
     /// Ok(())
     /// # }
     /// ```
-    pub async fn messages(&self) -> Result<Stream<'_>, Error> {
+    pub async fn messages(&self) -> Result<Stream<'_>, StreamError> {
         Stream::stream(
             BatchConfig {
                 batch: 200,
@@ -111,7 +113,7 @@ impl Consumer<Config> {
         &self,
         batch: I,
         inbox: String,
-    ) -> Result<(), Error> {
+    ) -> Result<(), StreamError> {
         let subject = format!(
             "{}.CONSUMER.MSG.NEXT.{}.{}",
             self.context.prefix, self.info.stream_name, self.info.name
@@ -245,7 +247,7 @@ impl Consumer<Config> {
     /// Ok(())
     /// # }
     /// ```
-    pub fn sequence(&self, batch: usize) -> Result<Sequence, Error> {
+    pub fn sequence(&self, batch: usize) -> Result<Sequence, StreamError> {
         let context = self.context.clone();
         let subject = format!(
             "{}.CONSUMER.MSG.NEXT.{}.{}",
@@ -275,7 +277,7 @@ pub struct Batch {
 }
 
 impl<'a> Batch {
-    async fn batch(batch: BatchConfig, consumer: &Consumer<Config>) -> Result<Batch, Error> {
+    async fn batch(batch: BatchConfig, consumer: &Consumer<Config>) -> Result<Batch, StreamError> {
         let inbox = consumer.context.client.new_inbox();
         let subscription = consumer.context.client.subscribe(inbox.clone()).await?;
         consumer.request_batch(batch, inbox.clone()).await?;
@@ -289,7 +291,7 @@ impl<'a> Batch {
 }
 
 impl futures::Stream for Batch {
-    type Item = Result<jetstream::Message, Error>;
+    type Item = Result<jetstream::Message, StreamError>;
 
     fn poll_next(
         mut self: std::pin::Pin<&mut Self>,
@@ -312,13 +314,10 @@ impl futures::Stream for Batch {
                         })))
                     }
                     status => {
-                        return Poll::Ready(Some(Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!(
-                                "eror while processing messages from the stream: {}, {:?}",
-                                status, message.description
-                            ),
-                        )))))
+                        return Poll::Ready(Some(Err(StreamError::Status {
+                            status,
+                            description: message.description,
+                        })))
                     }
                 },
                 None => Poll::Ready(None),
@@ -333,11 +332,11 @@ pub struct Sequence<'a> {
     subject: String,
     request: Bytes,
     pending_messages: usize,
-    next: Option<BoxFuture<'a, Result<Batch, Error>>>,
+    next: Option<BoxFuture<'a, Result<Batch, StreamError>>>,
 }
 
 impl<'a> futures::Stream for Sequence<'a> {
-    type Item = Result<Batch, Error>;
+    type Item = Result<Batch, StreamError>;
 
     fn poll_next(
         mut self: std::pin::Pin<&mut Self>,
@@ -393,14 +392,14 @@ pub struct Stream<'a> {
     inbox: String,
     subject: String,
     batch_config: BatchConfig,
-    request: Option<BoxFuture<'a, Result<(), Error>>>,
+    request: Option<BoxFuture<'a, Result<(), StreamError>>>,
 }
 
 impl<'a> Stream<'a> {
     async fn stream(
         batch_config: BatchConfig,
         consumer: &Consumer<Config>,
-    ) -> Result<Stream<'a>, Error> {
+    ) -> Result<Stream<'a>, StreamError> {
         let inbox = consumer.context.client.new_inbox();
         let subscription = consumer.context.client.subscribe(inbox.clone()).await?;
         let subject = format!(
@@ -421,7 +420,7 @@ impl<'a> Stream<'a> {
 }
 
 impl<'a> futures::Stream for Stream<'a> {
-    type Item = Result<jetstream::Message, Error>;
+    type Item = Result<jetstream::Message, StreamError>;
 
     fn poll_next(
         mut self: std::pin::Pin<&mut Self>,
@@ -486,13 +485,10 @@ impl<'a> futures::Stream for Stream<'a> {
                             })));
                         }
                         status => {
-                            return Poll::Ready(Some(Err(Box::new(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                format!(
-                                    "eror while processing messages from the stream: {}, {:?}",
-                                    status, message.description
-                                ),
-                            )))))
+                            return Poll::Ready(Some(Err(StreamError::Status {
+                                status,
+                                description: message.description,
+                            })))
                         }
                     },
                     None => return Poll::Ready(None),
@@ -720,7 +716,7 @@ impl<'a> StreamBuilder<'a> {
     /// }
     /// # Ok(())
     /// # }
-    pub async fn messages(self) -> Result<Stream<'a>, Error> {
+    pub async fn messages(self) -> Result<Stream<'a>, StreamError> {
         Stream::stream(
             BatchConfig {
                 batch: self.batch,
@@ -949,7 +945,7 @@ impl<'a> FetchBuilder<'a> {
     /// }
     /// # Ok(())
     /// # }
-    pub async fn messages(self) -> Result<Batch, Error> {
+    pub async fn messages(self) -> Result<Batch, StreamError> {
         Batch::batch(
             BatchConfig {
                 batch: self.batch,
@@ -1179,7 +1175,7 @@ impl<'a> BatchBuilder<'a> {
     /// }
     /// # Ok(())
     /// # }
-    pub async fn messages(self) -> Result<Batch, Error> {
+    pub async fn messages(self) -> Result<Batch, StreamError> {
         Batch::batch(
             BatchConfig {
                 batch: self.batch,
@@ -1327,12 +1323,11 @@ impl IntoConsumerConfig for Config {
     }
 }
 impl FromConsumer for Config {
-    fn try_from_consumer_config(config: consumer::Config) -> Result<Self, Error> {
+    fn try_from_consumer_config(config: consumer::Config) -> Result<Self, RequestError> {
         if config.deliver_subject.is_some() {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(RequestError::InvalidArgument(
                 "pull consumer cannot have delivery subject",
-            )));
+            ));
         }
         Ok(Config {
             durable_name: config.durable_name,
@@ -11,10 +11,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{AckPolicy, Consumer, DeliverPolicy, FromConsumer, IntoConsumerConfig, ReplayPolicy};
+use super::{
+    AckPolicy, Consumer, DeliverPolicy, FromConsumer, IntoConsumerConfig, ReplayPolicy, StreamError,
+};
 use crate::{
-    jetstream::{self, Context, Message},
-    Error, StatusCode, Subscriber,
+    jetstream::{self, Context, Message, RequestError},
+    StatusCode, Subscriber,
 };
 
 use bytes::Bytes;
@@ -61,7 +63,7 @@ impl Consumer<Config> {
     /// Ok(())
     /// # }
     /// ```
-    pub async fn messages(&self) -> Result<Messages, Error> {
+    pub async fn messages(&self) -> Result<Messages, StreamError> {
         let deliver_subject = self.info.config.deliver_subject.clone().unwrap();
         let subscriber = self.context.client.subscribe(deliver_subject).await?;
 
@@ -78,7 +80,7 @@ pub struct Messages {
 }
 
 impl futures::Stream for Messages {
-    type Item = Result<Message, Error>;
+    type Item = Result<Message, StreamError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
@@ -194,12 +196,11 @@ pub struct Config {
 }
 
 impl FromConsumer for Config {
-    fn try_from_consumer_config(config: super::Config) -> Result<Self, Error> {
+    fn try_from_consumer_config(config: super::Config) -> Result<Self, RequestError> {
         if config.deliver_subject.is_none() {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(RequestError::InvalidArgument(
                 "push consumer must have delivery subject",
-            )));
+            ));
         }
 
         Ok(Config {
@@ -297,15 +298,16 @@ pub struct OrderedConfig {
 }
 
 impl FromConsumer for OrderedConfig {
-    fn try_from_consumer_config(config: crate::jetstream::consumer::Config) -> Result<Self, Error>
+    fn try_from_consumer_config(
+        config: crate::jetstream::consumer::Config,
+    ) -> Result<Self, RequestError>
     where
         Self: Sized,
     {
         if config.deliver_subject.is_none() {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(RequestError::InvalidArgument(
                 "push consumer must have delivery subject",
-            )));
+            ));
         }
         Ok(OrderedConfig {
             deliver_subject: config.deliver_subject.unwrap(),
@@ -351,7 +353,7 @@ impl IntoConsumerConfig for OrderedConfig {
 }
 
 impl Consumer<OrderedConfig> {
-    pub async fn messages<'a>(self) -> Result<Ordered<'a>, Error> {
+    pub async fn messages<'a>(self) -> Result<Ordered<'a>, StreamError> {
         let subscriber = self
             .context
             .client
@@ -373,13 +375,13 @@ pub struct Ordered<'a> {
     context: Context,
     consumer: Consumer<OrderedConfig>,
     subscriber: Option<Subscriber>,
-    subscriber_future: Option<BoxFuture<'a, Result<Subscriber, Error>>>,
+    subscriber_future: Option<BoxFuture<'a, Result<Subscriber, StreamError>>>,
     stream_sequence: u64,
     consumer_sequence: u64,
 }
 
 impl<'a> futures::Stream for Ordered<'a> {
-    type Item = Result<Message, Error>;
+    type Item = Result<Message, StreamError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
@@ -427,22 +429,13 @@ impl<'a> futures::Stream for Ordered<'a> {
                                             if let Some(sequence) =
                                                 headers.get(crate::header::NATS_LAST_STREAM)
                                             {
-                                                let sequence: u64 = sequence
-                                                    .to_str()
-                                                    .map_err(|err| {
-                                                        Box::new(std::io::Error::new(
-                                                            std::io::ErrorKind::Other,
-                                                            format!(
-                                                                "could not parse header: {}",
-                                                                err
-                                                            ),
+                                                let sequence: u64 =
+                                                    sequence.as_str().parse().map_err(|err| {
+                                                        StreamError::InvalidMessage(format!(
+                                                            "could not parse header into u64: {}",
+                                                            err
                                                         ))
-                                                    })?
-                                                    .parse().map_err(|err|
-                                                           Box::new(std::io::Error::new(
-                                                                   std::io::ErrorKind::Other,
-                                                                   format!("could not parse header into u64: {}", err))
-                                                               ))?;
+                                                    })?;
 
                                                 if sequence != self.stream_sequence {
                                                     self.subscriber = None;
@@ -470,7 +463,9 @@ impl<'a> futures::Stream for Ordered<'a> {
                                             context: self.context.clone(),
                                         };
 
-                                        let info = jetstream_message.info()?;
+                                        let info = jetstream_message.info().map_err(|err| {
+                                            StreamError::InvalidMessage(err.to_string())
+                                        })?;
                                         if info.consumer_sequence != self.consumer_sequence + 1
                                             && info.stream_sequence != self.stream_sequence + 1
                                         {
@@ -498,8 +493,11 @@ async fn recreate_ephemeral_subscriber(
     config: OrderedConfig,
     stream_name: String,
     sequence: u64,
-) -> Result<Subscriber, Error> {
-    let stream = context.get_stream(stream_name.clone()).await?;
+) -> Result<Subscriber, StreamError> {
+    let stream = context
+        .get_stream(stream_name.clone())
+        .await
+        .map_err(StreamError::Recreate)?;
 
     let subscriber = context
         .client
@@ -519,6 +517,7 @@ async fn recreate_ephemeral_subscriber(
             deliver_policy,
             ..config
         })
-        .await?;
+        .await
+        .map_err(StreamError::Recreate)?;
     Ok(subscriber)
 }
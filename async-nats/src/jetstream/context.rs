@@ -13,46 +13,59 @@
 //
 //! Manage operations on [Context], create/delete/update [Stream][crate::jetstream::stream::Stream]
 
+use crate::header::HeaderMap;
 use crate::jetstream::account::Account;
-use crate::jetstream::publish::PublishAck;
-use crate::jetstream::response::Response;
-use crate::{Client, Error};
+use crate::jetstream::publish::{parse_ack, AsyncPublisher, Publish, PublishAck, PublishAckFuture};
+use crate::jetstream::response::{self, ErrorCode, Response};
+use crate::Client;
 use bytes::Bytes;
-use http::HeaderMap;
-use serde::{de::DeserializeOwned, Serialize};
+use futures::{Future, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{self, json};
 use std::borrow::Borrow;
-use std::io::{self, ErrorKind};
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{self, Poll};
+use std::time::Duration;
+use tokio::sync::OnceCell;
 
-use super::stream::{Config, DeleteStatus, Info, Stream};
+use super::kv::{Store, MAX_HISTORY};
+use super::object_store::{is_valid_bucket_name, ObjectStore};
+use super::stream::{Config, DeleteStatus, DiscardPolicy, Info, Stream};
+
+const DEFAULT_MAX_PENDING_ACKS: usize = 4000;
+const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// A context which can perform jetstream scoped requests.
 #[derive(Debug, Clone)]
 pub struct Context {
     pub(crate) client: Client,
     pub(crate) prefix: String,
+    pub(crate) max_pending_acks: usize,
+    pub(crate) ack_timeout: Duration,
+    // started on the first asynchronous publish and shared between clones.
+    pub(crate) publisher: Arc<OnceCell<Arc<AsyncPublisher>>>,
 }
 
 impl Context {
     pub(crate) fn new(client: Client) -> Context {
-        Context {
-            client,
-            prefix: "$JS.API".to_string(),
-        }
+        Context::with_prefix(client, "$JS.API")
     }
 
     pub(crate) fn with_prefix<T: ToString>(client: Client, prefix: T) -> Context {
         Context {
             client,
             prefix: prefix.to_string(),
+            max_pending_acks: DEFAULT_MAX_PENDING_ACKS,
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+            publisher: Arc::new(OnceCell::new()),
         }
     }
 
     pub(crate) fn with_domain<T: AsRef<str>>(client: Client, domain: T) -> Context {
-        Context {
-            client,
-            prefix: format!("$JS.{}.API", domain.as_ref()),
-        }
+        Context::with_prefix(client, format!("$JS.{}.API", domain.as_ref()))
     }
 
     /// Publish a message to a given subject associated with a stream and returns an acknowledgment from
@@ -72,21 +85,13 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn publish(&self, subject: String, payload: Bytes) -> Result<PublishAck, Error> {
-        let message = self.client.request(subject, payload).await?;
-        let response = serde_json::from_slice(message.payload.as_ref())?;
-
-        match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while publishing message: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
-
-            Response::Ok(publish_ack) => Ok(publish_ack),
-        }
+    pub async fn publish(
+        &self,
+        subject: String,
+        payload: Bytes,
+    ) -> Result<PublishAck, RequestError> {
+        self.send_publish(subject, Publish::new().payload(payload))
+            .await
     }
 
     /// Publish a message with headers to a given subject associated with a stream and returns an acknowledgment from
@@ -103,7 +108,7 @@ impl Context {
     /// let jetstream = async_nats::jetstream::new(client);
     ///
     /// let mut headers = async_nats::HeaderMap::new();
-    /// headers.append("X-key", b"Value".as_ref().try_into()?);
+    /// headers.append("X-key", "Value".parse()?);
     /// let ack = jetstream.publish_with_headers("events".to_string(), headers, "data".into()).await?;
     /// # Ok(())
     /// # }
@@ -113,38 +118,214 @@ impl Context {
         subject: String,
         headers: HeaderMap,
         payload: Bytes,
-    ) -> Result<PublishAck, Error> {
-        let message = self
-            .client
-            .request_with_headers(subject, headers, payload)
-            .await?;
-        let response = serde_json::from_slice(message.payload.as_ref())?;
+    ) -> Result<PublishAck, RequestError> {
+        self.send_publish(subject, Publish::new().headers(headers).payload(payload))
+            .await
+    }
 
-        match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while publishing message: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
-
-            Response::Ok(publish_ack) => Ok(publish_ack),
+    /// Publish a message built with [Publish], which can set the message id and the
+    /// expected state of the stream, and returns an acknowledgment from the server.
+    ///
+    /// If the expected last sequence or message id does not match, a
+    /// [RequestError::WrongLastSequence] or [RequestError::WrongLastMessageId] error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::jetstream::publish::Publish;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let ack = jetstream
+    ///     .send_publish(
+    ///         "events".to_string(),
+    ///         Publish::new()
+    ///             .payload("data".into())
+    ///             .message_id("event-1")
+    ///             .expected_stream("events"),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "jetstream_publish", skip_all, fields(subject = %subject))
+    )]
+    pub async fn send_publish(
+        &self,
+        subject: String,
+        publish: Publish,
+    ) -> Result<PublishAck, RequestError> {
+        let mut request = crate::Request::new().payload(publish.payload);
+        if let Some(headers) = publish.headers {
+            request = request.headers(headers);
+        }
+        if let Some(timeout) = publish.timeout {
+            request = request.timeout(Some(timeout));
+        }
+
+        let message = self.client.send_request(subject, request).await?;
+        parse_ack(message)
+    }
+
+    /// Publish a message to a given subject associated with a stream without waiting for the
+    /// acknowledgement, returning a [PublishAckFuture] which resolves to it.
+    ///
+    /// Acknowledgements arrive on a single inbox subscription shared by the context.
+    /// Once [Context::max_pending_acks] publishes are awaiting their acknowledgements, publishing
+    /// waits until one arrives or times out after [Context::ack_timeout].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let mut acks = Vec::new();
+    /// for i in 0..100 {
+    ///     acks.push(jetstream.publish_async("events".to_string(), i.to_string().into()).await?);
+    /// }
+    /// for ack in acks {
+    ///     println!("stored at {}", ack.await?.sequence);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish_async(
+        &self,
+        subject: String,
+        payload: Bytes,
+    ) -> Result<PublishAckFuture, RequestError> {
+        self.send_publish_async(subject, Publish::new().payload(payload))
+            .await
+    }
+
+    /// Publish a message built with [Publish] without waiting for the acknowledgement,
+    /// like [Context::publish_async]. The [Publish::timeout] overrides [Context::ack_timeout].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::jetstream::publish::Publish;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let ack = jetstream
+    ///     .send_publish_async(
+    ///         "events".to_string(),
+    ///         Publish::new().payload("data".into()).message_id("event-1"),
+    ///     )
+    ///     .await?;
+    /// ack.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "jetstream_publish_async", skip_all, fields(subject = %subject))
+    )]
+    pub async fn send_publish_async(
+        &self,
+        subject: String,
+        publish: Publish,
+    ) -> Result<PublishAckFuture, RequestError> {
+        let publisher = self
+            .publisher
+            .get_or_try_init(|| AsyncPublisher::start(&self.client, self.max_pending_acks))
+            .await
+            .map_err(|err| RequestError::Request(err.into()))?;
+
+        publisher
+            .publish(&self.client, subject, publish, self.ack_timeout)
+            .await
+    }
+
+    /// Waits until every message published with [Context::publish_async] has been acknowledged
+    /// or has timed out.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// for i in 0..100 {
+    ///     jetstream.publish_async("events".to_string(), i.to_string().into()).await?;
+    /// }
+    /// jetstream.wait_for_pending_acks().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_pending_acks(&self) {
+        if let Some(publisher) = self.publisher.get() {
+            publisher.wait_for_pending().await;
         }
     }
 
+    /// Returns the number of messages published with [Context::publish_async]
+    /// which are still awaiting their acknowledgement.
+    pub fn pending_acks(&self) -> usize {
+        self.publisher
+            .get()
+            .map(|publisher| publisher.pending())
+            .unwrap_or_default()
+    }
+
+    /// Sets how many messages published with [Context::publish_async] can await their
+    /// acknowledgement at once. Defaults to 4000, a limit of 0 is raised to 1.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client).max_pending_acks(256);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_pending_acks(mut self, max_pending_acks: usize) -> Context {
+        // without any permits, publishing would wait forever.
+        self.max_pending_acks = max_pending_acks.max(1);
+        // publishes already awaiting acknowledgements keep the previous limit.
+        self.publisher = Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Sets how long a message published with [Context::publish_async] waits for its
+    /// acknowledgement before failing with a timeout. Defaults to 5 seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use std::time::Duration;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client).ack_timeout(Duration::from_secs(1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Context {
+        self.ack_timeout = ack_timeout;
+        self
+    }
+
     /// Query the server for account information
-    pub async fn query_account(&self) -> Result<Account, Error> {
+    pub async fn query_account(&self) -> Result<Account, RequestError> {
         let response: Response<Account> = self.request("INFO".into(), b"").await?;
 
         match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while querying account information: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(account) => Ok(account),
         }
     }
@@ -171,28 +352,21 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn create_stream<S>(&self, stream_config: S) -> Result<Stream, Error>
+    pub async fn create_stream<S>(&self, stream_config: S) -> Result<Stream, RequestError>
     where
         Config: From<S>,
     {
         let config: Config = stream_config.into();
         if config.name.is_empty() {
-            return Err(Box::new(io::Error::new(
-                ErrorKind::InvalidInput,
+            return Err(RequestError::InvalidArgument(
                 "the stream name must not be empty",
-            )));
+            ));
         }
         let subject = format!("STREAM.CREATE.{}", config.name);
         let response: Response<Info> = self.request(subject, &config).await?;
 
         match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while creating stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(info) => Ok(Stream {
                 context: self.clone(),
                 info,
@@ -215,25 +389,18 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn get_stream<T: AsRef<str>>(&self, stream: T) -> Result<Stream, Error> {
+    pub async fn get_stream<T: AsRef<str>>(&self, stream: T) -> Result<Stream, RequestError> {
         let stream = stream.as_ref();
         if stream.is_empty() {
-            return Err(Box::new(io::Error::new(
-                ErrorKind::InvalidInput,
+            return Err(RequestError::InvalidArgument(
                 "the stream name must not be empty",
-            )));
+            ));
         }
 
         let subject = format!("STREAM.INFO.{}", stream);
         let request: Response<Info> = self.request(subject, &()).await?;
         match request {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(info) => Ok(Stream {
                 context: self.clone(),
                 info,
@@ -262,7 +429,7 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn get_or_create_stream<S>(&self, stream_config: S) -> Result<Stream, Error>
+    pub async fn get_or_create_stream<S>(&self, stream_config: S) -> Result<Stream, RequestError>
     where
         S: Into<Config>,
     {
@@ -272,13 +439,7 @@ impl Context {
         let request: Response<Info> = self.request(subject, &()).await?;
         match request {
             Response::Err { error } if error.status == 404 => self.create_stream(&config).await,
-            Response::Err { error } => Err(Box::new(io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting or creating stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(info) => Ok(Stream {
                 context: self.clone(),
                 info,
@@ -301,23 +462,19 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete_stream<T: AsRef<str>>(&self, stream: T) -> Result<DeleteStatus, Error> {
+    pub async fn delete_stream<T: AsRef<str>>(
+        &self,
+        stream: T,
+    ) -> Result<DeleteStatus, RequestError> {
         let stream = stream.as_ref();
         if stream.is_empty() {
-            return Err(Box::new(io::Error::new(
-                ErrorKind::InvalidInput,
+            return Err(RequestError::InvalidArgument(
                 "the stream name must not be empty",
-            )));
+            ));
         }
         let subject = format!("STREAM.DELETE.{}", stream);
         match self.request(subject, &json!({})).await? {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while deleting stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(delete_response) => Ok(delete_response),
         }
     }
@@ -344,24 +501,324 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn update_stream<S>(&self, config: S) -> Result<Info, Error>
+    pub async fn update_stream<S>(&self, config: S) -> Result<Info, RequestError>
     where
         S: Borrow<Config>,
     {
         let config = config.borrow();
         let subject = format!("STREAM.UPDATE.{}", config.name);
         match self.request(subject, config).await? {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while updating stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(info) => Ok(info),
         }
     }
 
+    /// Lists the names of all streams, requesting them from the server page by page.
+    /// Use [StreamNames::subject] to list only streams which capture a subject.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::TryStreamExt;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let mut names = jetstream.stream_names().subject("events.>");
+    /// while let Some(name) = names.try_next().await? {
+    ///     println!("stream: {}", name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_names(&self) -> StreamNames {
+        StreamNames {
+            paged: Paged::new(self.clone(), "STREAM.NAMES".to_string()),
+        }
+    }
+
+    /// Lists the [Info] of all streams, requesting them from the server page by page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::TryStreamExt;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let mut streams = jetstream.streams();
+    /// while let Some(info) = streams.try_next().await? {
+    ///     println!("stream {} has {} messages", info.config.name, info.state.messages);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn streams(&self) -> Streams {
+        Streams {
+            paged: Paged::new(self.clone(), "STREAM.LIST".to_string()),
+        }
+    }
+
+    /// Creates a new key-value [Store] bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_key_value(
+        &self,
+        config: crate::jetstream::kv::Config,
+    ) -> Result<Store, RequestError> {
+        if !self.client.is_server_compatible(2, 6, 2) {
+            return Err(RequestError::NotSupported(
+                "key-value requires at least server version 2.6.2",
+            ));
+        }
+
+        let discard_policy = {
+            if self.client.is_server_compatible(2, 7, 2) {
+                DiscardPolicy::New
+            } else {
+                DiscardPolicy::Old
+            }
+        };
+
+        if !crate::jetstream::kv::is_valid_bucket_name(&config.bucket) {
+            return Err(RequestError::InvalidArgument("invalid bucket name"));
+        }
+
+        let history = if config.history > 0 {
+            if config.history > MAX_HISTORY {
+                return Err(RequestError::InvalidArgument(
+                    "history limited to a max of 64",
+                ));
+            }
+
+            config.history
+        } else {
+            1
+        };
+
+        let num_replicas = if config.num_replicas == 0 {
+            1
+        } else {
+            config.num_replicas
+        };
+
+        let stream = self
+            .create_stream(Config {
+                name: format!("KV_{}", config.bucket),
+                description: Some(config.description),
+                subjects: vec![format!("$KV.{}.>", config.bucket)],
+                max_messages_per_subject: history,
+                max_bytes: config.max_bytes,
+                max_age: config.max_age,
+                max_message_size: config.max_value_size,
+                storage: config.storage,
+                allow_rollup: true,
+                deny_delete: true,
+                num_replicas,
+                discard: discard_policy,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(Store {
+            prefix: format!("$KV.{}.", config.bucket),
+            name: config.bucket,
+            stream_name: stream.info.config.name.clone(),
+            stream,
+        })
+    }
+
+    /// Gets an existing key-value [Store] bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.get_key_value("kv").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_key_value<T: Into<String>>(&self, bucket: T) -> Result<Store, RequestError> {
+        let bucket: String = bucket.into();
+        if !crate::jetstream::kv::is_valid_bucket_name(&bucket) {
+            return Err(RequestError::InvalidArgument("invalid bucket name"));
+        }
+
+        let stream_name = format!("KV_{}", &bucket);
+        let stream = self.get_stream(stream_name.clone()).await?;
+
+        if stream.info.config.max_messages_per_subject < 1 {
+            return Err(RequestError::InvalidArgument(
+                "bucket not valid key-value store",
+            ));
+        }
+
+        Ok(Store {
+            prefix: format!("$KV.{}.", bucket),
+            name: bucket,
+            stream_name,
+            stream,
+        })
+    }
+
+    /// Deletes a key-value [Store] bucket and all of its data.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// jetstream.delete_key_value("kv").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_key_value<T: AsRef<str>>(
+        &self,
+        bucket: T,
+    ) -> Result<DeleteStatus, RequestError> {
+        if !crate::jetstream::kv::is_valid_bucket_name(bucket.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid bucket name"));
+        }
+
+        let stream_name = format!("KV_{}", bucket.as_ref());
+        self.delete_stream(stream_name).await
+    }
+
+    /// Creates a new object store bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.create_object_store(async_nats::jetstream::object_store::Config {
+    ///     bucket: "bucket".to_string(),
+    ///     ..Default::default()
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_object_store(
+        &self,
+        config: super::object_store::Config,
+    ) -> Result<ObjectStore, RequestError> {
+        if !self.client.is_server_compatible(2, 6, 2) {
+            return Err(RequestError::NotSupported(
+                "object-store requires at least server version 2.6.2",
+            ));
+        }
+
+        if !is_valid_bucket_name(&config.bucket) {
+            return Err(RequestError::InvalidArgument("invalid bucket name"));
+        }
+
+        let bucket_name = config.bucket.clone();
+        let stream_name = format!("OBJ_{}", bucket_name);
+        let chunk_subject = format!("$O.{}.C.>", bucket_name);
+        let meta_subject = format!("$O.{}.M.>", bucket_name);
+
+        let stream = self
+            .create_stream(Config {
+                name: stream_name,
+                description: config.description.clone(),
+                subjects: vec![chunk_subject, meta_subject],
+                max_age: config.max_age,
+                storage: config.storage,
+                num_replicas: config.num_replicas,
+                discard: DiscardPolicy::New,
+                allow_rollup: true,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(ObjectStore {
+            name: bucket_name,
+            stream,
+        })
+    }
+
+    /// Gets an existing object store bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("bucket").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_object_store<T: AsRef<str>>(
+        &self,
+        bucket_name: T,
+    ) -> Result<ObjectStore, RequestError> {
+        let bucket_name = bucket_name.as_ref();
+        if !is_valid_bucket_name(bucket_name) {
+            return Err(RequestError::InvalidArgument("invalid bucket name"));
+        }
+
+        let stream_name = format!("OBJ_{}", bucket_name);
+        let stream = self.get_stream(stream_name).await?;
+
+        Ok(ObjectStore {
+            name: bucket_name.to_string(),
+            stream,
+        })
+    }
+
+    /// Deletes an object store bucket and all of its objects.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// jetstream.delete_object_store("bucket").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_object_store<T: AsRef<str>>(
+        &self,
+        bucket_name: T,
+    ) -> Result<DeleteStatus, RequestError> {
+        let stream_name = format!("OBJ_{}", bucket_name.as_ref());
+        self.delete_stream(stream_name).await
+    }
+
     /// Send a request to the jetstream JSON API.
     ///
     /// This is a low level API used mostly internally, that should be used only in
@@ -382,7 +839,15 @@ impl Context {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn request<T, V>(&self, subject: String, payload: &T) -> Result<Response<V>, Error>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "jetstream_request", skip_all, fields(subject = %subject))
+    )]
+    pub async fn request<T, V>(
+        &self,
+        subject: String,
+        payload: &T,
+    ) -> Result<Response<V>, RequestError>
     where
         T: ?Sized + Serialize,
         V: DeserializeOwned,
@@ -398,3 +863,264 @@ impl Context {
         Ok(response)
     }
 }
+
+/// An error returned from JetStream API requests, made either directly with [Context::request]
+/// or through the [Context], [Stream] and [Consumer][crate::jetstream::consumer::Consumer] APIs.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RequestError {
+    /// Sending the request or receiving the response failed.
+    /// [crate::RequestError::NoResponders] means that JetStream is not enabled, or when
+    /// publishing, that there is no stream for the subject.
+    Request(crate::RequestError),
+    /// The request could not be serialized or the response could not be parsed.
+    Serialization(serde_json::Error),
+    /// The server rejected the request. Use [RequestError::error_code] to check what went wrong.
+    Api(response::Error),
+    /// An invalid argument was passed, e.g. an empty stream name.
+    InvalidArgument(&'static str),
+    /// The server version does not support the requested feature.
+    NotSupported(&'static str),
+    /// The server response could not be interpreted, e.g. an undecodable message payload.
+    InvalidResponse(&'static str),
+    /// A published message was rejected, because the last sequence of the stream or subject
+    /// did not match the expected one.
+    WrongLastSequence(response::Error),
+    /// A published message was rejected, because the id of the last message in the stream
+    /// did not match the expected one.
+    WrongLastMessageId(response::Error),
+}
+
+impl RequestError {
+    /// Returns the JetStream [ErrorCode] if the request was rejected by the server.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::jetstream::response::ErrorCode;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// match jetstream.get_stream("events").await {
+    ///     Ok(stream) => println!("stream info: {:?}", stream.cached_info()),
+    ///     Err(err) if err.error_code() == Some(ErrorCode::STREAM_NOT_FOUND) => {
+    ///         println!("stream does not exist")
+    ///     }
+    ///     Err(err) => return Err(err.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            RequestError::Api(error)
+            | RequestError::WrongLastSequence(error)
+            | RequestError::WrongLastMessageId(error) => Some(error.error_code()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Request(err) => err.fmt(f),
+            RequestError::Serialization(err) => {
+                write!(
+                    f,
+                    "nats: failed to serialize JetStream API message: {}",
+                    err
+                )
+            }
+            RequestError::Api(err) => write!(f, "nats: JetStream API error: {}", err),
+            RequestError::InvalidArgument(description) => write!(f, "nats: {}", description),
+            RequestError::NotSupported(description) => {
+                write!(f, "nats: not supported by the server: {}", description)
+            }
+            RequestError::InvalidResponse(description) => {
+                write!(f, "nats: invalid response: {}", description)
+            }
+            RequestError::WrongLastSequence(err) => {
+                write!(f, "nats: wrong last sequence: {}", err)
+            }
+            RequestError::WrongLastMessageId(err) => {
+                write!(f, "nats: wrong last message id: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RequestError::Request(err) => Some(err),
+            RequestError::Serialization(err) => Some(err),
+            RequestError::Api(err)
+            | RequestError::WrongLastSequence(err)
+            | RequestError::WrongLastMessageId(err) => Some(err),
+            RequestError::InvalidArgument(_)
+            | RequestError::NotSupported(_)
+            | RequestError::InvalidResponse(_) => None,
+        }
+    }
+}
+
+impl From<crate::RequestError> for RequestError {
+    fn from(err: crate::RequestError) -> Self {
+        RequestError::Request(err)
+    }
+}
+
+impl From<serde_json::Error> for RequestError {
+    fn from(err: serde_json::Error) -> Self {
+        RequestError::Serialization(err)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PagedRequest {
+    offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PagedResponse<T> {
+    total: usize,
+    #[serde(alias = "streams", alias = "consumers")]
+    items: Option<Vec<T>>,
+}
+
+type PageFuture<T> =
+    Pin<Box<dyn Future<Output = Result<Response<PagedResponse<T>>, RequestError>> + Send>>;
+
+/// Requests items of a paged JetStream API one page at a time, as they are consumed.
+pub(crate) struct Paged<T> {
+    context: Context,
+    subject: String,
+    filter: Option<String>,
+    offset: usize,
+    done: bool,
+    items: VecDeque<T>,
+    page: Option<PageFuture<T>>,
+}
+
+impl<T> Paged<T> {
+    pub(crate) fn new(context: Context, subject: String) -> Paged<T> {
+        Paged {
+            context,
+            subject,
+            filter: None,
+            offset: 0,
+            done: false,
+            items: VecDeque::new(),
+            page: None,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Paged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Paged")
+            .field("subject", &self.subject)
+            .field("filter", &self.filter)
+            .field("offset", &self.offset)
+            .field("items", &self.items)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> futures::Stream for Paged<T>
+where
+    T: DeserializeOwned + Send + Unpin + 'static,
+{
+    type Item = Result<T, RequestError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.items.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            let page = match this.page.as_mut() {
+                Some(page) => page,
+                None => {
+                    let context = this.context.clone();
+                    let subject = this.subject.clone();
+                    let request = PagedRequest {
+                        offset: this.offset,
+                        subject: this.filter.clone(),
+                    };
+                    this.page.insert(Box::pin(
+                        async move { context.request(subject, &request).await },
+                    ))
+                }
+            };
+
+            let response = match page.as_mut().poll(cx) {
+                Poll::Ready(response) => response,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.page = None;
+
+            match response {
+                Ok(Response::Ok(page)) => {
+                    let items = page.items.unwrap_or_default();
+                    this.offset += items.len();
+                    this.done = items.is_empty() || this.offset >= page.total;
+                    this.items.extend(items);
+                }
+                Ok(Response::Err { error }) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(RequestError::Api(error))));
+                }
+                Err(err) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+    }
+}
+
+/// A stream of stream names, returned by [Context::stream_names].
+#[derive(Debug)]
+pub struct StreamNames {
+    paged: Paged<String>,
+}
+
+impl StreamNames {
+    /// Lists only the streams which capture the subject, which can contain wildcards.
+    pub fn subject<T: Into<String>>(mut self, subject: T) -> StreamNames {
+        self.paged.filter = Some(subject.into());
+        self
+    }
+}
+
+impl futures::Stream for StreamNames {
+    type Item = Result<String, RequestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.paged.poll_next_unpin(cx)
+    }
+}
+
+/// A stream of stream [Info], returned by [Context::streams].
+#[derive(Debug)]
+pub struct Streams {
+    paged: Paged<Info>,
+}
+
+impl futures::Stream for Streams {
+    type Item = Result<Info, RequestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.paged.poll_next_unpin(cx)
+    }
+}
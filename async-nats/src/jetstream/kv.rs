@@ -0,0 +1,811 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A Key-Value store built on top of JetStream.
+//! This feature is experimental and the API may change.
+
+use std::io::{self, ErrorKind};
+use std::task::Poll;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+use crate::header::{self, HeaderMap, HeaderValue};
+use crate::Error;
+
+use super::consumer::push::{Ordered, OrderedConfig};
+use super::consumer::{DeliverPolicy, ReplayPolicy};
+use super::context::RequestError;
+use super::response::Response;
+use super::stream::{self, GetRawMessage, StorageType, Stream};
+
+/// Configuration values for key value stores.
+#[derive(Debug, Default)]
+pub struct Config {
+    /// Name of the bucket
+    pub bucket: String,
+    /// Human readable description.
+    pub description: String,
+    /// Maximum size of a single value.
+    pub max_value_size: i32,
+    /// Maximum historical entries.
+    pub history: i64,
+    /// Maximum age of any entry in the bucket, or zero for no limit.
+    /// It is sent to the server in nanoseconds, as the `max_age` of the underlying stream.
+    pub max_age: Duration,
+    /// How large the bucket may become in total bytes before the configured discard policy kicks in
+    pub max_bytes: i64,
+    /// The type of storage backend, `File` (default) and `Memory`
+    pub storage: StorageType,
+    /// How many replicas to keep for each entry in a cluster.
+    pub num_replicas: usize,
+}
+
+pub(crate) const MAX_HISTORY: i64 = 64;
+const ALL_KEYS: &str = ">";
+
+const KV_OPERATION: &str = "KV-Operation";
+const KV_OPERATION_DELETE: &str = "DEL";
+const KV_OPERATION_PURGE: &str = "PURGE";
+
+const ROLLUP_SUBJECT: &str = "sub";
+
+lazy_static! {
+    static ref VALID_BUCKET_RE: Regex = Regex::new(r#"\A[a-zA-Z0-9_-]+\z"#).unwrap();
+    static ref VALID_KEY_RE: Regex = Regex::new(r#"\A[-/_=\.a-zA-Z0-9]+\z"#).unwrap();
+}
+
+pub(crate) fn is_valid_bucket_name(bucket_name: &str) -> bool {
+    VALID_BUCKET_RE.is_match(bucket_name)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    if key.is_empty() || key.starts_with('.') || key.ends_with('.') {
+        return false;
+    }
+
+    VALID_KEY_RE.is_match(key)
+}
+
+/// Describes what kind of operation and entry represents
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Operation {
+    /// A value was put into the bucket
+    Put,
+    /// A value was deleted from a bucket
+    Delete,
+    /// A value was purged from a bucket
+    Purge,
+}
+
+impl Operation {
+    fn from_value(value: &str) -> Operation {
+        match value {
+            KV_OPERATION_DELETE => Operation::Delete,
+            KV_OPERATION_PURGE => Operation::Purge,
+            _ => Operation::Put,
+        }
+    }
+}
+
+// Helper to extract key value operation from message headers
+fn kv_operation_from_maybe_headers(maybe_headers: Option<&HeaderMap>) -> Operation {
+    if let Some(headers) = maybe_headers {
+        if let Some(op) = headers.get(KV_OPERATION) {
+            return Operation::from_value(op.as_str());
+        }
+    }
+
+    Operation::Put
+}
+
+// Helper to extract key value operation from the raw headers returned by the
+// stream message get API.
+fn kv_operation_from_raw_headers(raw_headers: &[u8]) -> Operation {
+    let raw_headers = String::from_utf8_lossy(raw_headers);
+    for line in raw_headers.lines().skip(1) {
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim().eq_ignore_ascii_case(KV_OPERATION) {
+                return Operation::from_value(value.trim());
+            }
+        }
+    }
+
+    Operation::Put
+}
+
+/// An entry in a key-value bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Name of the bucket the entry is in.
+    pub bucket: String,
+    /// The key that was retrieved.
+    pub key: String,
+    /// The value that was retreived.
+    pub value: Bytes,
+    /// A unique sequence for this value.
+    pub revision: u64,
+    /// Distance from the latest value.
+    pub delta: u64,
+    /// The time the data was put in the bucket.
+    pub created: time::OffsetDateTime,
+    /// The kind of operation that caused this entry.
+    pub operation: Operation,
+}
+
+/// A key value store
+#[derive(Debug, Clone)]
+pub struct Store {
+    pub(crate) name: String,
+    pub(crate) stream_name: String,
+    pub(crate) prefix: String,
+    pub(crate) stream: Stream,
+}
+
+impl Store {
+    /// Returns the status of the bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.get_key_value("kv").await?;
+    /// let status = kv.status().await?;
+    /// println!("status: {:?}", status);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&self) -> Result<Status, RequestError> {
+        let subject = format!("STREAM.INFO.{}", self.stream_name);
+
+        match self.stream.context.request(subject, &json!({})).await? {
+            Response::Ok::<stream::Info>(info) => Ok(Status {
+                info,
+                bucket: self.name.to_string(),
+            }),
+            Response::Err { error } => Err(RequestError::Api(error)),
+        }
+    }
+
+    /// Places the new value for the key into the bucket and returns its revision.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let revision = kv.put("key", "value".into()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn put<T: AsRef<str>>(&self, key: T, value: Bytes) -> Result<u64, RequestError> {
+        if !is_valid_key(key.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid key"));
+        }
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let publish_ack = self.stream.context.publish(subject, value).await?;
+
+        Ok(publish_ack.sequence)
+    }
+
+    /// Retrieves the last [Entry] for a given key from a bucket, including delete and purge
+    /// markers.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let status = kv.put("key", "value".into()).await?;
+    /// let entry = kv.entry("key").await?;
+    /// println!("entry: {:?}", entry);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn entry<T: Into<String>>(&self, key: T) -> Result<Option<Entry>, RequestError> {
+        let key: String = key.into();
+        if !is_valid_key(key.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid key"));
+        }
+
+        let subject = format!("STREAM.MSG.GET.{}", self.stream_name);
+        let payload = json!({
+            "last_by_subj": format!("{}{}", self.prefix, key),
+        });
+
+        let response: Response<GetRawMessage> =
+            self.stream.context.request(subject, &payload).await?;
+        match response {
+            Response::Ok(value) => {
+                let message = value.message;
+                let operation = match message.headers {
+                    Some(ref headers) if !headers.is_empty() => {
+                        let headers = base64::decode(headers).map_err(|_| {
+                            RequestError::InvalidResponse("entry headers are not valid base64")
+                        })?;
+                        kv_operation_from_raw_headers(&headers)
+                    }
+                    _ => Operation::Put,
+                };
+                let value = base64::decode(message.payload).map_err(|_| {
+                    RequestError::InvalidResponse("entry payload is not valid base64")
+                })?;
+
+                Ok(Some(Entry {
+                    bucket: self.name.clone(),
+                    key,
+                    value: value.into(),
+                    revision: message.sequence,
+                    delta: 0,
+                    created: message.time,
+                    operation,
+                }))
+            }
+            Response::Err { error } if error.status == 404 => Ok(None),
+            Response::Err { error } => Err(RequestError::Api(error)),
+        }
+    }
+
+    /// Retrieves the latest value for a given key, if any.
+    /// Returns `None` if the key does not exist or was deleted or purged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let value = kv.get("key").await?;
+    /// match value {
+    ///     Some(bytes) => println!("value: {:?}", bytes),
+    ///     None => println!("key does not exist"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get<T: Into<String>>(&self, key: T) -> Result<Option<Bytes>, RequestError> {
+        match self.entry(key).await? {
+            Some(entry) => match entry.operation {
+                Operation::Put => Ok(Some(entry.value)),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Creates the key/value pair if it does not exist or is marked for deletion.
+    /// Fails with [RequestError::WrongLastSequence] if the key already has a value.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let revision = kv.create("key", "value".into()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create<T: AsRef<str>>(&self, key: T, value: Bytes) -> Result<u64, RequestError> {
+        let err = match self.update(key.as_ref(), value.clone(), 0).await {
+            Err(RequestError::WrongLastSequence(err)) => err,
+            result => return result,
+        };
+
+        // the key exists, which is fine if its last entry is a delete or purge marker.
+        match self.entry(key.as_ref()).await? {
+            Some(entry) if entry.operation != Operation::Put => {
+                self.update(key.as_ref(), value, entry.revision).await
+            }
+            _ => Err(RequestError::WrongLastSequence(err)),
+        }
+    }
+
+    /// Updates the value if the latest revision of the key matches the given one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let revision = kv.put("key", "value".into()).await?;
+    /// kv.update("key", "updated".into(), revision).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update<T: AsRef<str>>(
+        &self,
+        key: T,
+        value: Bytes,
+        revision: u64,
+    ) -> Result<u64, RequestError> {
+        if !is_valid_key(key.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid key"));
+        }
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::NATS_EXPECTED_LAST_SUBJECT_SEQUENCE,
+            HeaderValue::from(revision),
+        );
+
+        let publish_ack = self
+            .stream
+            .context
+            .publish_with_headers(subject, headers, value)
+            .await?;
+
+        Ok(publish_ack.sequence)
+    }
+
+    /// Marks an entry as deleted by placing a delete marker but leaves the revision history intact.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// kv.put("key", "value".into()).await?;
+    /// kv.delete("key").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete<T: AsRef<str>>(&self, key: T) -> Result<(), RequestError> {
+        if !is_valid_key(key.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid key"));
+        }
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(KV_OPERATION, HeaderValue::from_static(KV_OPERATION_DELETE));
+
+        self.stream
+            .context
+            .publish_with_headers(subject, headers, Bytes::new())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes all entries associated with the key, including all historical revisions,
+    /// leaving only a purge marker.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// kv.put("key", "value".into()).await?;
+    /// kv.put("key", "another".into()).await?;
+    /// kv.purge("key").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn purge<T: AsRef<str>>(&self, key: T) -> Result<(), RequestError> {
+        if !is_valid_key(key.as_ref()) {
+            return Err(RequestError::InvalidArgument("invalid key"));
+        }
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(KV_OPERATION, HeaderValue::from_static(KV_OPERATION_PURGE));
+        headers.insert(
+            header::NATS_ROLLUP,
+            HeaderValue::from_static(ROLLUP_SUBJECT),
+        );
+
+        self.stream
+            .context
+            .publish_with_headers(subject, headers, Bytes::new())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Creates a [futures::Stream] over [Entries][Entry] for a given key pattern.
+    /// It first yields the latest entry for every matching key, followed by any later changes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let mut entries = kv.watch("kv").await?;
+    /// while let Some(entry) = entries.next().await {
+    ///     println!("entry: {:?}", entry);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn watch<'a, T: AsRef<str>>(&self, key: T) -> Result<Watch<'a>, Error> {
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let consumer = self
+            .stream
+            .create_consumer(OrderedConfig {
+                deliver_subject: self.stream.context.client.new_inbox(),
+                description: Some("kv watch consumer".to_string()),
+                filter_subject: subject,
+                replay_policy: ReplayPolicy::Instant,
+                deliver_policy: DeliverPolicy::LastPerSubject,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(Watch {
+            subscription: consumer.messages().await?,
+            prefix: self.prefix.clone(),
+            bucket: self.name.clone(),
+        })
+    }
+
+    /// Creates a [futures::Stream] over [Entries][Entry] for all keys in the bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let mut entries = kv.watch_all().await?;
+    /// while let Some(entry) = entries.next().await {
+    ///     println!("entry: {:?}", entry);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn watch_all<'a>(&self) -> Result<Watch<'a>, Error> {
+        self.watch(ALL_KEYS).await
+    }
+
+    /// Returns a [futures::Stream] that yields all historical [Entries][Entry] for a given key,
+    /// oldest first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let mut entries = kv.history("kv").await?;
+    /// while let Some(entry) = entries.next().await {
+    ///     println!("entry: {:?}", entry);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn history<'a, T: AsRef<str>>(&self, key: T) -> Result<History<'a>, Error> {
+        let subject = format!("{}{}", self.prefix, key.as_ref());
+
+        let consumer = self
+            .stream
+            .create_consumer(OrderedConfig {
+                deliver_subject: self.stream.context.client.new_inbox(),
+                description: Some("kv history consumer".to_string()),
+                filter_subject: subject,
+                replay_policy: ReplayPolicy::Instant,
+                deliver_policy: DeliverPolicy::All,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(History {
+            done: consumer.info.num_pending == 0,
+            subscription: consumer.messages().await?,
+            prefix: self.prefix.clone(),
+            bucket: self.name.clone(),
+        })
+    }
+
+    /// Returns a [futures::Stream] over all the current keys in the bucket.
+    /// Deleted and purged keys are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::TryStreamExt;
+    /// let client = async_nats::connect("demo.nats.io:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let kv = jetstream.create_key_value(async_nats::jetstream::kv::Config {
+    ///     bucket: "kv".to_string(),
+    ///     history: 10,
+    ///     ..Default::default()
+    /// }).await?;
+    /// let keys: Vec<String> = kv.keys().await?.try_collect().await?;
+    /// println!("keys: {:?}", keys);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn keys<'a>(&self) -> Result<Keys<'a>, Error> {
+        let subject = format!("{}{}", self.prefix, ALL_KEYS);
+
+        let consumer = self
+            .stream
+            .create_consumer(OrderedConfig {
+                deliver_subject: self.stream.context.client.new_inbox(),
+                description: Some("kv keys consumer".to_string()),
+                filter_subject: subject,
+                headers_only: true,
+                replay_policy: ReplayPolicy::Instant,
+                deliver_policy: DeliverPolicy::LastPerSubject,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(Keys {
+            inner: History {
+                done: consumer.info.num_pending == 0,
+                subscription: consumer.messages().await?,
+                prefix: self.prefix.clone(),
+                bucket: self.name.clone(),
+            },
+        })
+    }
+
+    /// Returns the name of the bucket.
+    pub fn bucket(&self) -> &str {
+        &self.name
+    }
+}
+
+fn entry_from_message(
+    message: super::Message,
+    prefix: &str,
+    bucket: &str,
+) -> Result<(Entry, u64), Error> {
+    let info = message.info()?;
+    let revision = info.stream_sequence;
+    let created = info.published;
+    let pending = info.pending;
+
+    let operation = kv_operation_from_maybe_headers(message.headers.as_ref());
+    let key = message
+        .subject
+        .strip_prefix(prefix)
+        .map(|key| key.to_string())
+        .ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("subject {} does not belong to the bucket", message.subject),
+            )
+        })?;
+
+    Ok((
+        Entry {
+            bucket: bucket.to_string(),
+            key,
+            value: message.message.payload,
+            revision,
+            delta: pending,
+            created,
+            operation,
+        },
+        pending,
+    ))
+}
+
+/// A [futures::Stream] of [Entries][Entry] created by [Store::watch] and [Store::watch_all].
+pub struct Watch<'a> {
+    subscription: Ordered<'a>,
+    prefix: String,
+    bucket: String,
+}
+
+impl<'a> futures::Stream for Watch<'a> {
+    type Item = Result<Entry, Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        match self.subscription.poll_next_unpin(cx) {
+            Poll::Ready(message) => match message {
+                None => Poll::Ready(None),
+                Some(message) => {
+                    let (entry, _) = entry_from_message(message?, &self.prefix, &self.bucket)?;
+                    Poll::Ready(Some(Ok(entry)))
+                }
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [futures::Stream] of historical [Entries][Entry] created by [Store::history].
+/// It finishes once the latest entry has been yielded.
+pub struct History<'a> {
+    subscription: Ordered<'a>,
+    done: bool,
+    prefix: String,
+    bucket: String,
+}
+
+impl<'a> futures::Stream for History<'a> {
+    type Item = Result<Entry, Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.subscription.poll_next_unpin(cx) {
+            Poll::Ready(message) => match message {
+                None => Poll::Ready(None),
+                Some(message) => {
+                    let (entry, pending) =
+                        entry_from_message(message?, &self.prefix, &self.bucket)?;
+                    if pending == 0 {
+                        self.done = true;
+                    }
+                    Poll::Ready(Some(Ok(entry)))
+                }
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [futures::Stream] of keys in a bucket created by [Store::keys].
+pub struct Keys<'a> {
+    inner: History<'a>,
+}
+
+impl<'a> futures::Stream for Keys<'a> {
+    type Item = Result<String, Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.inner.poll_next_unpin(cx) {
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(entry)) => {
+                    let entry = entry?;
+                    // We are only interested in unique current keys from subjects so we skip
+                    // delete and purge markers.
+                    if entry.operation != Operation::Put {
+                        continue;
+                    }
+                    return Poll::Ready(Some(Ok(entry.key)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Represents status information about a key value store bucket.
+#[derive(Debug)]
+pub struct Status {
+    /// The stream info of the stream backing the bucket.
+    pub info: stream::Info,
+    /// The name of the bucket.
+    pub bucket: String,
+}
+
+impl Status {
+    /// The name of the bucket.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// How many messages are in the bucket, including historical values.
+    pub fn values(&self) -> u64 {
+        self.info.state.messages
+    }
+
+    /// Configured history kept per key.
+    pub fn history(&self) -> i64 {
+        self.info.config.max_messages_per_subject
+    }
+
+    /// How long the bucket keeps values for.
+    pub fn max_age(&self) -> Duration {
+        self.info.config.max_age
+    }
+}
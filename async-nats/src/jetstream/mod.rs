@@ -88,12 +88,14 @@ use crate::Client;
 pub mod account;
 pub mod consumer;
 pub mod context;
+pub mod kv;
 pub mod message;
+pub mod object_store;
 pub mod publish;
 pub mod response;
 pub mod stream;
 
-pub use context::Context;
+pub use context::{Context, RequestError};
 pub use message::{AckKind, Message};
 
 /// Creates a new JetStream [Context] that provides JetStream API for managming and using [Streams][crate::jetstream::stream::Stream],
@@ -0,0 +1,605 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Object Store module
+//! This feature is experimental and the API may change.
+
+use std::io::{self, ErrorKind};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::{Buf, Bytes};
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+
+use crate::header::{self, HeaderMap, HeaderValue};
+use crate::Error;
+
+use super::consumer::push::{Ordered, OrderedConfig};
+use super::consumer::{DeliverPolicy, ReplayPolicy};
+use super::context::RequestError;
+use super::response::Response;
+use super::stream::{StorageType, Stream};
+
+const DEFAULT_CHUNK_SIZE: usize = 128 * 1024;
+const ROLLUP_SUBJECT: &str = "sub";
+const DIGEST_PREFIX: &str = "SHA-256=";
+
+lazy_static! {
+    static ref BUCKET_NAME_RE: Regex = Regex::new(r#"\A[a-zA-Z0-9_-]+\z"#).unwrap();
+    static ref OBJECT_NAME_RE: Regex = Regex::new(r#"\A[-/_=\.a-zA-Z0-9]+\z"#).unwrap();
+}
+
+pub(crate) fn is_valid_bucket_name(bucket_name: &str) -> bool {
+    BUCKET_NAME_RE.is_match(bucket_name)
+}
+
+pub(crate) fn is_valid_object_name(object_name: &str) -> bool {
+    if object_name.is_empty() || object_name.starts_with('.') || object_name.ends_with('.') {
+        return false;
+    }
+
+    OBJECT_NAME_RE.is_match(object_name)
+}
+
+pub(crate) fn sanitize_object_name(object_name: &str) -> String {
+    object_name.replace(['.', ' '], "_")
+}
+
+fn encode_digest(digest: Sha256) -> String {
+    format!(
+        "{}{}",
+        DIGEST_PREFIX,
+        base64::encode_config(digest.finalize(), base64::URL_SAFE)
+    )
+}
+
+/// Configuration values for object store buckets.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Name of the storage bucket.
+    pub bucket: String,
+    /// A short description of the purpose of this storage bucket.
+    pub description: Option<String>,
+    /// Maximum age of any value in the bucket, or zero for no limit.
+    /// It is sent to the server in nanoseconds, as the `max_age` of the underlying stream.
+    pub max_age: Duration,
+    /// The type of storage backend, `File` (default) and `Memory`
+    pub storage: StorageType,
+    /// How many replicas to keep for each value in a cluster, maximum 5.
+    pub num_replicas: usize,
+}
+
+/// A blob store capable of storing large objects efficiently in streams.
+#[derive(Debug, Clone)]
+pub struct ObjectStore {
+    pub(crate) name: String,
+    pub(crate) stream: Stream,
+}
+
+impl ObjectStore {
+    /// Retrieves the current information for the object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    /// let info = bucket.info("FOO").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn info<T: AsRef<str>>(&self, object_name: T) -> Result<ObjectInfo, Error> {
+        let object_name = sanitize_object_name(object_name.as_ref());
+        if !is_valid_object_name(&object_name) {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid object name",
+            )));
+        }
+
+        // Grab last meta value we have.
+        let subject = format!("$O.{}.M.{}", &self.name, &object_name);
+
+        let message = self
+            .stream
+            .get_last_raw_message_by_subject(subject.as_str())
+            .await?;
+        let decoded_payload = base64::decode(message.payload).map_err(|err| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode object info: {}", err),
+            )
+        })?;
+        let object_info = serde_json::from_slice::<ObjectInfo>(&decoded_payload)?;
+
+        Ok(object_info)
+    }
+
+    /// Seals the object store from further modifications.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    /// bucket.seal().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn seal(&self) -> Result<(), Error> {
+        let mut stream = self.stream.clone();
+        let mut config = stream.info().await?.config.clone();
+        config.sealed = true;
+
+        self.stream.context.update_stream(&config).await?;
+
+        Ok(())
+    }
+
+    /// Puts the contents of the given [AsyncRead] into the object store, returning the
+    /// [ObjectInfo] of the newly stored object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    /// let mut file = tokio::fs::File::open("Cargo.toml").await?;
+    /// bucket.put("FOO", &mut file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn put<T>(
+        &self,
+        meta: T,
+        data: &mut (impl AsyncRead + Unpin),
+    ) -> Result<ObjectInfo, Error>
+    where
+        ObjectMeta: From<T>,
+    {
+        let object_meta: ObjectMeta = meta.into();
+        let object_name = sanitize_object_name(&object_meta.name);
+        if !is_valid_object_name(&object_name) {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid object name",
+            )));
+        }
+
+        // Fetch any existing object info, if there is any for later use.
+        let maybe_existing_object_info = self.info(&object_name).await.ok();
+
+        let object_nuid = nuid::next();
+        let chunk_subject = format!("$O.{}.C.{}", &self.name, &object_nuid);
+
+        let mut object_chunks = 0;
+        let mut object_size = 0;
+        let mut digest = Sha256::new();
+
+        let mut buffer = vec![0; DEFAULT_CHUNK_SIZE];
+
+        loop {
+            // Fill up the whole chunk, unless the reader is exhausted.
+            let mut n = 0;
+            while n < buffer.len() {
+                let read = data.read(&mut buffer[n..]).await?;
+                if read == 0 {
+                    break;
+                }
+                n += read;
+            }
+            if n == 0 {
+                break;
+            }
+
+            object_size += n;
+            object_chunks += 1;
+            digest.update(&buffer[..n]);
+
+            self.stream
+                .context
+                .publish(chunk_subject.clone(), Bytes::copy_from_slice(&buffer[..n]))
+                .await?;
+
+            if n < buffer.len() {
+                break;
+            }
+        }
+
+        let subject = format!("$O.{}.M.{}", &self.name, &object_name);
+        let object_info = ObjectInfo {
+            name: object_name,
+            description: object_meta.description,
+            link: object_meta.link,
+            bucket: self.name.clone(),
+            nuid: object_nuid,
+            chunks: object_chunks,
+            size: object_size,
+            digest: encode_digest(digest),
+            modified: OffsetDateTime::now_utc(),
+            deleted: false,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::NATS_ROLLUP,
+            HeaderValue::from_static(ROLLUP_SUBJECT),
+        );
+        let data = serde_json::to_vec(&object_info)?;
+
+        // Publish metadata
+        self.stream
+            .context
+            .publish_with_headers(subject, headers, data.into())
+            .await?;
+
+        // Purge any old chunks.
+        if let Some(existing_object_info) = maybe_existing_object_info {
+            self.purge_chunks(&existing_object_info.nuid).await?;
+        }
+
+        Ok(object_info)
+    }
+
+    /// Gets an [Object] from the store, which can be read with [AsyncRead].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use tokio::io::AsyncReadExt;
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    /// let mut object = bucket.get("FOO").await?;
+    ///
+    /// let mut bytes = Vec::new();
+    /// object.read_to_end(&mut bytes).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get<'a, T: AsRef<str>>(&self, object_name: T) -> Result<Object<'a>, Error> {
+        let object_info = self.info(object_name).await?;
+        if let Some(link) = object_info.link {
+            if link.name.is_empty() {
+                return Err(Box::new(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "object is a link to a bucket",
+                )));
+            }
+
+            // Links may point to objects in other buckets.
+            let linked_store = match link.bucket {
+                Some(ref bucket) if bucket != &self.name => {
+                    Some(self.stream.context.get_object_store(bucket).await?)
+                }
+                _ => None,
+            };
+            let store = linked_store.as_ref().unwrap_or(self);
+
+            let linked_info = store.info(&link.name).await?;
+            if linked_info.deleted {
+                return Err(Box::new(io::Error::new(
+                    ErrorKind::NotFound,
+                    "linked object is deleted",
+                )));
+            }
+            if linked_info.link.is_some() {
+                return Err(Box::new(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "link points to another link",
+                )));
+            }
+
+            return store.get_chunks(linked_info).await;
+        }
+
+        self.get_chunks(object_info).await
+    }
+
+    async fn get_chunks<'a>(&self, object_info: ObjectInfo) -> Result<Object<'a>, Error> {
+        // There is nothing to read for empty or deleted objects.
+        if object_info.chunks == 0 {
+            return Ok(Object::new(None, object_info));
+        }
+
+        let chunk_subject = format!("$O.{}.C.{}", self.name, object_info.nuid);
+        let consumer = self
+            .stream
+            .create_consumer(OrderedConfig {
+                deliver_subject: self.stream.context.client.new_inbox(),
+                filter_subject: chunk_subject,
+                replay_policy: ReplayPolicy::Instant,
+                deliver_policy: DeliverPolicy::All,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(Object::new(Some(consumer.messages().await?), object_info))
+    }
+
+    /// Places a delete marker and purges the data stream associated with the object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    /// bucket.delete("FOO").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete<T: AsRef<str>>(&self, object_name: T) -> Result<(), Error> {
+        let object_name = sanitize_object_name(object_name.as_ref());
+        let mut object_info = self.info(&object_name).await?;
+        object_info.chunks = 0;
+        object_info.size = 0;
+        object_info.deleted = true;
+
+        let data = serde_json::to_vec(&object_info)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::NATS_ROLLUP,
+            HeaderValue::from_static(ROLLUP_SUBJECT),
+        );
+
+        let subject = format!("$O.{}.M.{}", &self.name, &object_name);
+
+        self.stream
+            .context
+            .publish_with_headers(subject, headers, data.into())
+            .await?;
+
+        self.purge_chunks(&object_info.nuid).await?;
+
+        Ok(())
+    }
+
+    /// Creates a [futures::Stream] of [ObjectInfo] updates for all objects in the store.
+    /// It first yields the latest info of every object, followed by any later changes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let bucket = jetstream.get_object_store("store").await?;
+    ///
+    /// let mut watcher = bucket.watch().await?;
+    /// while let Some(object) = watcher.next().await {
+    ///     println!("detected changes in {:?}", object?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn watch<'a>(&self) -> Result<Watch<'a>, Error> {
+        let subject = format!("$O.{}.M.>", self.name);
+        let consumer = self
+            .stream
+            .create_consumer(OrderedConfig {
+                deliver_subject: self.stream.context.client.new_inbox(),
+                description: Some("object store watcher".to_string()),
+                filter_subject: subject,
+                replay_policy: ReplayPolicy::Instant,
+                deliver_policy: DeliverPolicy::LastPerSubject,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(Watch {
+            subscription: consumer.messages().await?,
+        })
+    }
+
+    async fn purge_chunks(&self, nuid: &str) -> Result<(), RequestError> {
+        let subject = format!("STREAM.PURGE.{}", self.stream.info.config.name);
+        let chunk_subject = format!("$O.{}.C.{}", self.name, nuid);
+
+        match self
+            .stream
+            .context
+            .request(subject, &json!({ "filter": chunk_subject }))
+            .await?
+        {
+            Response::Ok::<PurgeResponse>(_) => Ok(()),
+            Response::Err { error } => Err(RequestError::Api(error)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PurgeResponse {
+    #[allow(dead_code)]
+    success: bool,
+}
+
+/// A [futures::Stream] of [ObjectInfo] updates created by [ObjectStore::watch].
+pub struct Watch<'a> {
+    subscription: Ordered<'a>,
+}
+
+impl<'a> futures::Stream for Watch<'a> {
+    type Item = Result<ObjectInfo, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.subscription.poll_next_unpin(cx) {
+            Poll::Ready(message) => match message {
+                Some(message) => {
+                    let message = message?;
+                    let object_info = serde_json::from_slice::<ObjectInfo>(&message.payload)?;
+                    Poll::Ready(Some(Ok(object_info)))
+                }
+                None => Poll::Ready(None),
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Represents an object stored in a bucket, which can be read with [AsyncRead].
+pub struct Object<'a> {
+    info: ObjectInfo,
+    subscription: Option<Ordered<'a>>,
+    remaining_bytes: Bytes,
+    has_pending_messages: bool,
+}
+
+impl<'a> Object<'a> {
+    pub(crate) fn new(subscription: Option<Ordered<'a>>, info: ObjectInfo) -> Self {
+        Object {
+            has_pending_messages: subscription.is_some(),
+            subscription,
+            info,
+            remaining_bytes: Bytes::new(),
+        }
+    }
+
+    /// Returns information about the object.
+    pub fn info(&self) -> &ObjectInfo {
+        &self.info
+    }
+}
+
+impl<'a> AsyncRead for Object<'a> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        // read data accumulated in remaining bytes into the buffer.
+        if !self.remaining_bytes.is_empty() {
+            let len = std::cmp::min(buf.remaining(), self.remaining_bytes.len());
+            buf.put_slice(&self.remaining_bytes[..len]);
+            self.remaining_bytes.advance(len);
+            return Poll::Ready(Ok(()));
+        }
+
+        if !self.has_pending_messages {
+            return Poll::Ready(Ok(()));
+        }
+
+        let subscription = match self.subscription.as_mut() {
+            Some(subscription) => subscription,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        match subscription.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(message))) => {
+                let info = message
+                    .info()
+                    .map_err(|err| io::Error::new(ErrorKind::Other, err))?;
+                if info.pending == 0 {
+                    self.has_pending_messages = false;
+                }
+
+                let mut payload = message.message.payload;
+                let len = std::cmp::min(buf.remaining(), payload.len());
+                buf.put_slice(&payload[..len]);
+                payload.advance(len);
+                self.remaining_bytes = payload;
+
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Err(io::Error::new(ErrorKind::Other, err))),
+            Poll::Ready(None) => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Meta and instance information about an object.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ObjectInfo {
+    /// Name of the object
+    pub name: String,
+    /// A short human readable description of the object.
+    pub description: Option<String>,
+    /// Link this object points to, if any.
+    pub link: Option<ObjectLink>,
+    /// Name of the bucket the object is stored in.
+    pub bucket: String,
+    /// Unique identifier used to uniquely identify this version of the object.
+    pub nuid: String,
+    /// Size in bytes of the object.
+    pub size: usize,
+    /// Number of chunks the object is stored in.
+    pub chunks: usize,
+    /// Date and time the object was last modified.
+    #[serde(with = "rfc3339")]
+    pub modified: OffsetDateTime,
+    /// Digest of the object stream.
+    pub digest: String,
+    /// Set to true if the object has been deleted.
+    pub deleted: bool,
+}
+
+/// Meta information about an object.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ObjectMeta {
+    /// Name of the object
+    pub name: String,
+    /// A short human readable description of the object.
+    pub description: Option<String>,
+    /// Link this object points to, if any.
+    pub link: Option<ObjectLink>,
+}
+
+impl From<&str> for ObjectMeta {
+    fn from(s: &str) -> ObjectMeta {
+        ObjectMeta {
+            name: s.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A link to another object, potentially in another bucket.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ObjectLink {
+    /// Name of the object
+    pub name: String,
+    /// Name of the bucket the object is stored in.
+    pub bucket: Option<String>,
+}
@@ -12,8 +12,24 @@
 // limitations under the License.
 //
 //! Publish `JetStream` messages.
+use super::context::RequestError;
+use super::response::{ErrorCode, Response};
+use crate::header::HeaderMap;
+use crate::{Client, Message, StatusCode};
+use bytes::Bytes;
+use futures::{Future, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ops::Not;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{oneshot, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+// How often acknowledgements which did not arrive in time are failed.
+const ACK_EXPIRY_INTERVAL: Duration = Duration::from_millis(100);
 
 /// `PublishAck` is an acknowledgement received after successfully publishing a message.
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -30,3 +46,317 @@ pub struct PublishAck {
     #[serde(default, skip_serializing_if = "Not::not")]
     pub duplicate: bool,
 }
+
+/// Used for building customized publishes, sent with
+/// [Context::send_publish][crate::jetstream::Context::send_publish].
+///
+/// The expectations make the server reject the message unless the stream is in the expected
+/// state, which allows appending to a stream or a subject only if nothing was written since it
+/// was last read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), async_nats::Error> {
+/// use async_nats::jetstream::{context::RequestError, publish::Publish};
+///
+/// let client = async_nats::connect("localhost:4222").await?;
+/// let jetstream = async_nats::jetstream::new(client);
+///
+/// let publish = Publish::new()
+///     .payload("deposited 10".into())
+///     .message_id("deposit-1")
+///     .expected_last_subject_sequence(41);
+///
+/// match jetstream.send_publish("accounts.1".into(), publish).await {
+///     Ok(ack) => println!("appended at {}", ack.sequence),
+///     Err(RequestError::WrongLastSequence(_)) => println!("account changed in the meantime"),
+///     Err(err) => return Err(err.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Publish {
+    pub(crate) payload: Bytes,
+    pub(crate) headers: Option<HeaderMap>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl Publish {
+    /// Creates a new, empty publish.
+    pub fn new() -> Publish {
+        Default::default()
+    }
+
+    /// Sets the payload. If not used, empty payload will be sent.
+    pub fn payload(mut self, payload: Bytes) -> Publish {
+        self.payload = payload;
+        self
+    }
+
+    /// Sets the headers, replacing the ones set by the other methods so far.
+    pub fn headers(mut self, headers: HeaderMap) -> Publish {
+        self.headers = Some(headers);
+        self
+    }
+
+    /// Sets the message id, used by the stream to discard duplicates within its
+    /// [duplicate window][crate::jetstream::stream::Config::duplicate_window].
+    pub fn message_id(mut self, id: &str) -> Publish {
+        self.headers
+            .get_or_insert_with(HeaderMap::new)
+            .set_msg_id(id);
+        self
+    }
+
+    /// Rejects the message unless the subject belongs to the given stream.
+    pub fn expected_stream(mut self, stream: &str) -> Publish {
+        self.headers
+            .get_or_insert_with(HeaderMap::new)
+            .set_expected_stream(stream);
+        self
+    }
+
+    /// Rejects the message unless the last message in the stream had the given id.
+    pub fn expected_last_message_id(mut self, id: &str) -> Publish {
+        self.headers
+            .get_or_insert_with(HeaderMap::new)
+            .set_expected_last_msg_id(id);
+        self
+    }
+
+    /// Rejects the message unless the last message in the stream had the given sequence.
+    pub fn expected_last_sequence(mut self, sequence: u64) -> Publish {
+        self.headers
+            .get_or_insert_with(HeaderMap::new)
+            .set_expected_last_sequence(sequence);
+        self
+    }
+
+    /// Rejects the message unless the last message on its subject had the given sequence.
+    /// `0` expects that there are no messages on the subject yet.
+    pub fn expected_last_subject_sequence(mut self, sequence: u64) -> Publish {
+        self.headers
+            .get_or_insert_with(HeaderMap::new)
+            .set_expected_last_subject_sequence(sequence);
+        self
+    }
+
+    /// Sets a custom timeout for the acknowledgement, overriding the client request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Publish {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Parses the response to a publish, turning failed expectations into distinct errors.
+pub(crate) fn parse_ack(message: Message) -> Result<PublishAck, RequestError> {
+    if message.status == Some(StatusCode::NO_RESPONDERS) {
+        return Err(RequestError::Request(crate::RequestError::NoResponders));
+    }
+
+    match serde_json::from_slice(message.payload.as_ref())? {
+        Response::Err { error } => match error.error_code() {
+            ErrorCode::STREAM_WRONG_LAST_SEQUENCE => Err(RequestError::WrongLastSequence(error)),
+            ErrorCode::STREAM_WRONG_LAST_MESSAGE_ID => Err(RequestError::WrongLastMessageId(error)),
+            _ => Err(RequestError::Api(error)),
+        },
+        Response::Ok(publish_ack) => Ok(publish_ack),
+    }
+}
+
+/// A future resolving to the [PublishAck] of a message published with
+/// [Context::publish_async][crate::jetstream::Context::publish_async].
+///
+/// The acknowledgement is tracked even if the future is dropped, until it arrives or times out.
+#[derive(Debug)]
+pub struct PublishAckFuture {
+    receiver: oneshot::Receiver<Result<Message, crate::RequestError>>,
+}
+
+impl Future for PublishAckFuture {
+    type Output = Result<PublishAck, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| match result {
+                Ok(Ok(message)) => parse_ack(message),
+                Ok(Err(err)) => Err(RequestError::Request(err)),
+                Err(_) => Err(RequestError::Request(crate::RequestError::Closed)),
+            })
+    }
+}
+
+#[derive(Debug)]
+struct PendingAck {
+    sender: oneshot::Sender<Result<Message, crate::RequestError>>,
+    deadline: Instant,
+    // frees a place in the window once the acknowledgement arrives or times out.
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Publishes messages without waiting for their acknowledgements, which arrive on a single
+/// wildcard inbox subscription shared by all publishes of a [Context][crate::jetstream::Context].
+#[derive(Debug)]
+pub(crate) struct AsyncPublisher {
+    prefix: String,
+    pending: Mutex<HashMap<String, PendingAck>>,
+    window: Arc<Semaphore>,
+    drained: Notify,
+}
+
+impl AsyncPublisher {
+    /// Subscribes to the acknowledgements inbox and starts dispatching them.
+    /// The dispatching task ends once the publisher is dropped.
+    pub(crate) async fn start(
+        client: &Client,
+        max_pending: usize,
+    ) -> Result<Arc<AsyncPublisher>, crate::SubscribeError> {
+        let prefix = format!("{}.", client.new_inbox());
+        let mut subscriber = client.subscribe(format!("{}*", prefix)).await?;
+
+        let publisher = Arc::new(AsyncPublisher {
+            prefix,
+            pending: Mutex::new(HashMap::new()),
+            window: Arc::new(Semaphore::new(max_pending)),
+            drained: Notify::new(),
+        });
+
+        let weak = Arc::downgrade(&publisher);
+        tokio::spawn(async move {
+            let mut expiry = tokio::time::interval(ACK_EXPIRY_INTERVAL);
+            loop {
+                tokio::select! {
+                    message = subscriber.next() => {
+                        let publisher = match weak.upgrade() {
+                            Some(publisher) => publisher,
+                            None => break,
+                        };
+                        match message {
+                            Some(message) => publisher.complete(message),
+                            None => {
+                                publisher.fail_all();
+                                break;
+                            }
+                        }
+                    }
+                    _ = expiry.tick() => match weak.upgrade() {
+                        Some(publisher) => publisher.expire(Instant::now()),
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        Ok(publisher)
+    }
+
+    pub(crate) async fn publish(
+        &self,
+        client: &Client,
+        subject: String,
+        publish: Publish,
+        default_timeout: Duration,
+    ) -> Result<PublishAckFuture, RequestError> {
+        // the semaphore is never closed.
+        let permit = self.window.clone().acquire_owned().await.unwrap();
+
+        let token = nuid::next();
+        let (sender, receiver) = oneshot::channel();
+        let deadline = Instant::now() + publish.timeout.unwrap_or(default_timeout);
+        self.pending.lock().unwrap().insert(
+            token.clone(),
+            PendingAck {
+                sender,
+                deadline,
+                _permit: permit,
+            },
+        );
+
+        let reply = format!("{}{}", self.prefix, token);
+        let result = match publish.headers {
+            Some(headers) => {
+                client
+                    .publish_with_reply_and_headers(subject, reply, headers, publish.payload)
+                    .await
+            }
+            None => {
+                client
+                    .publish_with_reply(subject, reply, publish.payload)
+                    .await
+            }
+        };
+        if let Err(err) = result {
+            self.remove(&token);
+            return Err(RequestError::Request(err.into()));
+        }
+
+        Ok(PublishAckFuture { receiver })
+    }
+
+    pub(crate) fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub(crate) async fn wait_for_pending(&self) {
+        loop {
+            // registered before checking, so that a notification in between is not missed.
+            let drained = self.drained.notified();
+            if self.pending() == 0 {
+                return;
+            }
+            drained.await;
+        }
+    }
+
+    fn remove(&self, token: &str) -> Option<PendingAck> {
+        let mut pending = self.pending.lock().unwrap();
+        let ack = pending.remove(token);
+        if pending.is_empty() {
+            self.drained.notify_waiters();
+        }
+        ack
+    }
+
+    fn complete(&self, message: Message) {
+        let token = match message.subject.strip_prefix(&self.prefix) {
+            Some(token) => token.to_string(),
+            None => return,
+        };
+        if let Some(ack) = self.remove(&token) {
+            ack.sender.send(Ok(message)).ok();
+        }
+    }
+
+    fn expire(&self, now: Instant) {
+        let mut pending = self.pending.lock().unwrap();
+        let expired: Vec<String> = pending
+            .iter()
+            .filter(|(_, ack)| ack.deadline <= now)
+            .map(|(token, _)| token.clone())
+            .collect();
+        if expired.is_empty() {
+            return;
+        }
+
+        for token in expired {
+            if let Some(ack) = pending.remove(&token) {
+                ack.sender.send(Err(crate::RequestError::TimedOut)).ok();
+            }
+        }
+        if pending.is_empty() {
+            self.drained.notify_waiters();
+        }
+    }
+
+    fn fail_all(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        for (_, ack) in pending.drain() {
+            ack.sender.send(Err(crate::RequestError::Closed)).ok();
+        }
+        self.drained.notify_waiters();
+    }
+}
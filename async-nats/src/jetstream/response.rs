@@ -13,10 +13,49 @@
 
 //! A low level `JetStream` responses.
 
+use std::fmt;
+
 use serde::Deserialize;
 
+/// A JetStream error code, as returned by the server in the `err_code` field of an [Error].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode(pub u64);
+
+impl ErrorCode {
+    /// Bad request.
+    pub const BAD_REQUEST: ErrorCode = ErrorCode(10003);
+    /// Consumer name already in use with a different configuration.
+    pub const CONSUMER_NAME_EXIST: ErrorCode = ErrorCode(10013);
+    /// Consumer not found.
+    pub const CONSUMER_NOT_FOUND: ErrorCode = ErrorCode(10014);
+    /// No message found.
+    pub const NO_MESSAGE_FOUND: ErrorCode = ErrorCode(10037);
+    /// JetStream not enabled for account.
+    pub const NOT_ENABLED_FOR_ACCOUNT: ErrorCode = ErrorCode(10039);
+    /// Stream name already in use with a different configuration.
+    pub const STREAM_NAME_EXIST: ErrorCode = ErrorCode(10058);
+    /// Stream not found.
+    pub const STREAM_NOT_FOUND: ErrorCode = ErrorCode(10059);
+    /// Expected stream does not match.
+    pub const STREAM_NOT_MATCH: ErrorCode = ErrorCode(10060);
+    /// Wrong last msg ID.
+    pub const STREAM_WRONG_LAST_MESSAGE_ID: ErrorCode = ErrorCode(10070);
+    /// Wrong last sequence.
+    pub const STREAM_WRONG_LAST_SEQUENCE: ErrorCode = ErrorCode(10071);
+    /// JetStream not enabled.
+    pub const NOT_ENABLED: ErrorCode = ErrorCode(10076);
+    /// Invalid operation on sealed stream.
+    pub const STREAM_SEALED: ErrorCode = ErrorCode(10109);
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// An error description returned in a response to a jetstream request.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Error {
     /// Error code
     #[serde(rename = "err_code")]
@@ -30,6 +69,25 @@ pub struct Error {
     pub description: String,
 }
 
+impl Error {
+    /// Returns the JetStream [ErrorCode] of this error.
+    pub fn error_code(&self) -> ErrorCode {
+        ErrorCode(self.code)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (code {}, error code {})",
+            self.description, self.status, self.code
+        )
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// A response returned from a request to jetstream.
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
@@ -13,24 +13,28 @@
 //
 //! Manage operations on a [Stream], create/delete/update [Consumer][crate::jetstream::consumer::Consumer].
 
-use std::{
-    io::{self, ErrorKind},
-    time::Duration,
-};
+use std::pin::Pin;
+use std::task::{self, Poll};
+use std::time::Duration;
 
-use crate::Error;
+use bytes::Bytes;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use time::format_description::well_known::Rfc3339;
 use time::serde::rfc3339;
 
 use super::{
     consumer::{self, Consumer, FromConsumer, IntoConsumerConfig},
-    response::Response,
-    Context,
+    context::Paged,
+    response::{self, ErrorCode, Response},
+    Context, RequestError,
 };
+use crate::header::{HeaderMap, NATS_SEQUENCE, NATS_STREAM, NATS_SUBJECT, NATS_TIME_STAMP};
+use crate::StatusCode;
 
 /// Handle to operations that can be performed on a `Stream`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stream {
     pub(crate) info: Info,
     pub(crate) context: Context,
@@ -55,7 +59,7 @@ impl Stream {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn info(&mut self) -> Result<&Info, Error> {
+    pub async fn info(&mut self) -> Result<&Info, RequestError> {
         let subject = format!("STREAM.INFO.{}", self.info.config.name);
 
         match self.context.request(subject, &json!({})).await? {
@@ -63,13 +67,7 @@ impl Stream {
                 self.info = info;
                 Ok(&self.info)
             }
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting stream info: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
         }
     }
 
@@ -120,7 +118,7 @@ impl Stream {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn get_raw_message(&self, sequence: u64) -> Result<RawMessage, Error> {
+    pub async fn get_raw_message(&self, sequence: u64) -> Result<RawMessage, RequestError> {
         let subject = format!("STREAM.MSG.GET.{}", &self.info.config.name);
         let payload = json!({
             "seq": sequence,
@@ -128,13 +126,7 @@ impl Stream {
 
         let response: Response<GetRawMessage> = self.context.request(subject, &payload).await?;
         match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting message: {}, {}",
-                    error.code, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(value) => Ok(value.message),
         }
     }
@@ -167,7 +159,7 @@ impl Stream {
     pub async fn get_last_raw_message_by_subject(
         &self,
         stream_subject: &str,
-    ) -> Result<RawMessage, Error> {
+    ) -> Result<RawMessage, RequestError> {
         let subject = format!("STREAM.MSG.GET.{}", &self.info.config.name);
         let payload = json!({
             "last_by_subj":  stream_subject,
@@ -175,17 +167,124 @@ impl Stream {
 
         let response: Response<GetRawMessage> = self.context.request(subject, &payload).await?;
         match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting message: {}, {}",
-                    error.code, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(value) => Ok(value.message),
         }
     }
 
+    /// Gets a message from the stream with the Direct Get API, which can be answered by any
+    /// replica or mirror of a stream created with [Config::allow_direct] instead of only by the
+    /// stream leader.
+    ///
+    /// A missing message is reported with the [ErrorCode::NO_MESSAGE_FOUND] error code.
+    /// Batched Direct Get requests are not supported, messages can be iterated with
+    /// [Stream::direct_get_next_for_subject] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let context = async_nats::jetstream::new(client);
+    ///
+    /// let stream = context.get_or_create_stream(async_nats::jetstream::stream::Config {
+    ///     name: "events".to_string(),
+    ///     allow_direct: true,
+    ///     ..Default::default()
+    /// }).await?;
+    ///
+    /// let publish_ack = context.publish("events".to_string(), "data".into()).await?;
+    /// let message = stream.direct_get(publish_ack.sequence).await?;
+    /// println!("Retrieved message {:?}", message.payload);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn direct_get(&self, sequence: u64) -> Result<StreamMessage, RequestError> {
+        self.direct_get_request(json!({
+            "seq": sequence,
+        }))
+        .await
+    }
+
+    /// Gets the first message on the subject, which can contain wildcards, with a sequence equal
+    /// to or after the given one, or from the start of the stream, with the Direct Get API.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let context = async_nats::jetstream::new(client);
+    ///
+    /// let stream = context.get_stream("events").await?;
+    /// let mut sequence = 0;
+    /// loop {
+    ///     let message = stream
+    ///         .direct_get_next_for_subject("events.orders", Some(sequence + 1))
+    ///         .await?;
+    ///     sequence = message.sequence;
+    /// }
+    /// # }
+    /// ```
+    pub async fn direct_get_next_for_subject(
+        &self,
+        subject: &str,
+        sequence: Option<u64>,
+    ) -> Result<StreamMessage, RequestError> {
+        let payload = match sequence {
+            Some(sequence) => json!({
+                "next_by_subj": subject,
+                "seq": sequence,
+            }),
+            None => json!({
+                "next_by_subj": subject,
+            }),
+        };
+
+        self.direct_get_request(payload).await
+    }
+
+    /// Gets the last message on the subject with the Direct Get API.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let context = async_nats::jetstream::new(client);
+    ///
+    /// let stream = context.get_stream("events").await?;
+    /// let message = stream.direct_get_last_for_subject("events.orders").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn direct_get_last_for_subject(
+        &self,
+        subject: &str,
+    ) -> Result<StreamMessage, RequestError> {
+        self.direct_get_request(json!({
+            "last_by_subj": subject,
+        }))
+        .await
+    }
+
+    async fn direct_get_request(
+        &self,
+        payload: serde_json::Value,
+    ) -> Result<StreamMessage, RequestError> {
+        let subject = format!(
+            "{}.DIRECT.GET.{}",
+            self.context.prefix, self.info.config.name
+        );
+        let request = serde_json::to_vec(&payload).map(Bytes::from)?;
+
+        let message = self.context.client.request(subject, request).await?;
+        StreamMessage::from_direct(message)
+    }
+
     /// Delete a message from the stream.
     ///
     /// # Examples
@@ -207,7 +306,7 @@ impl Stream {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete_message(&self, sequence: u64) -> Result<bool, Error> {
+    pub async fn delete_message(&self, sequence: u64) -> Result<bool, RequestError> {
         let subject = format!("STREAM.MSG.DELETE.{}", &self.info.config.name);
         let payload = json!({
             "seq": sequence,
@@ -216,17 +315,59 @@ impl Stream {
         let response: Response<DeleteStatus> = self.context.request(subject, &payload).await?;
 
         match response {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while deleting message: {}, {}",
-                    error.code, error.status
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok(value) => Ok(value.success),
         }
     }
 
+    /// Purges all messages from the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let stream = jetstream.get_stream("events").await?;
+    /// let response = stream.purge().await?;
+    /// println!("purged {} messages", response.purged);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn purge(&self) -> Result<PurgeResponse, RequestError> {
+        self.send_purge(Purge::new()).await
+    }
+
+    /// Purges the messages of the stream selected by [Purge], which can limit the purge to a
+    /// subject, keep a number of the newest messages or purge up to a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use async_nats::jetstream::stream::Purge;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let stream = jetstream.get_stream("events").await?;
+    /// stream
+    ///     .send_purge(Purge::new().filter("events.old").keep(10))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_purge(&self, purge: Purge) -> Result<PurgeResponse, RequestError> {
+        let subject = format!("STREAM.PURGE.{}", self.info.config.name);
+
+        match self.context.request(subject, &purge).await? {
+            Response::Ok(response) => Ok(response),
+            Response::Err { error } => Err(RequestError::Api(error)),
+        }
+    }
+
     /// Create a new `Durable` or `Ephemeral` Consumer (if `durable_name` was not provided) and
     /// returns the info from the server about created [Consumer][Consumer]
     ///
@@ -250,7 +391,7 @@ impl Stream {
     pub async fn create_consumer<C: IntoConsumerConfig + FromConsumer>(
         &self,
         config: C,
-    ) -> Result<Consumer<C>, Error> {
+    ) -> Result<Consumer<C>, RequestError> {
         let config = config.into_consumer_config();
         let subject = if let Some(ref durable_name) = config.durable_name {
             format!(
@@ -269,13 +410,7 @@ impl Stream {
             )
             .await?
         {
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while creating stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok::<consumer::Info>(info) => Ok(Consumer::new(
                 FromConsumer::try_from_consumer_config(info.clone().config)?,
                 info,
@@ -300,20 +435,17 @@ impl Stream {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn consumer_info<T: AsRef<str>>(&self, name: T) -> Result<consumer::Info, Error> {
+    pub async fn consumer_info<T: AsRef<str>>(
+        &self,
+        name: T,
+    ) -> Result<consumer::Info, RequestError> {
         let name = name.as_ref();
 
         let subject = format!("CONSUMER.INFO.{}.{}", self.info.config.name, name);
 
         match self.context.request(subject, &json!({})).await? {
             Response::Ok(info) => Ok(info),
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting consumer info: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
         }
     }
 
@@ -338,7 +470,7 @@ impl Stream {
     pub async fn get_consumer<T: FromConsumer + IntoConsumerConfig>(
         &self,
         name: &str,
-    ) -> Result<Consumer<T>, Error> {
+    ) -> Result<Consumer<T>, RequestError> {
         let info = self.consumer_info(name).await?;
 
         Ok(Consumer::new(
@@ -374,18 +506,12 @@ impl Stream {
         &self,
         name: &str,
         config: T,
-    ) -> Result<Consumer<T>, Error> {
+    ) -> Result<Consumer<T>, RequestError> {
         let subject = format!("CONSUMER.INFO.{}.{}", self.info.config.name, name);
 
         match self.context.request(subject, &json!({})).await? {
             Response::Err { error } if error.status == 404 => self.create_consumer(config).await,
-            Response::Err { error } => Err(Box::new(io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while getting or creating stream: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
             Response::Ok::<consumer::Info>(info) => Ok(Consumer::new(
                 T::try_from_consumer_config(info.config.clone())?,
                 info,
@@ -411,20 +537,100 @@ impl Stream {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete_consumer(&self, name: &str) -> Result<DeleteStatus, Error> {
+    pub async fn delete_consumer(&self, name: &str) -> Result<DeleteStatus, RequestError> {
         let subject = format!("CONSUMER.DELETE.{}.{}", self.info.config.name, name);
 
         match self.context.request(subject, &json!({})).await? {
             Response::Ok(delete_status) => Ok(delete_status),
-            Response::Err { error } => Err(Box::new(std::io::Error::new(
-                ErrorKind::Other,
-                format!(
-                    "nats: error while deleting consumer: {}, {}, {}",
-                    error.code, error.status, error.description
-                ),
-            ))),
+            Response::Err { error } => Err(RequestError::Api(error)),
+        }
+    }
+
+    /// Lists the names of all consumers of the stream, requesting them from the server
+    /// page by page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::TryStreamExt;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let stream = jetstream.get_stream("events").await?;
+    /// let names: Vec<String> = stream.consumer_names().try_collect().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn consumer_names(&self) -> ConsumerNames {
+        ConsumerNames {
+            paged: Paged::new(
+                self.context.clone(),
+                format!("CONSUMER.NAMES.{}", self.info.config.name),
+            ),
         }
     }
+
+    /// Lists the [Info][consumer::Info] of all consumers of the stream, requesting them from
+    /// the server page by page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::TryStreamExt;
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let jetstream = async_nats::jetstream::new(client);
+    ///
+    /// let stream = jetstream.get_stream("events").await?;
+    /// let mut consumers = stream.consumers();
+    /// while let Some(info) = consumers.try_next().await? {
+    ///     println!(
+    ///         "consumer {} has {} pending and {} awaiting ack",
+    ///         info.name, info.num_pending, info.num_ack_pending
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn consumers(&self) -> Consumers {
+        Consumers {
+            paged: Paged::new(
+                self.context.clone(),
+                format!("CONSUMER.LIST.{}", self.info.config.name),
+            ),
+        }
+    }
+}
+
+/// A stream of consumer names, returned by [Stream::consumer_names].
+#[derive(Debug)]
+pub struct ConsumerNames {
+    paged: Paged<String>,
+}
+
+impl futures::Stream for ConsumerNames {
+    type Item = Result<String, RequestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.paged.poll_next_unpin(cx)
+    }
+}
+
+/// A stream of consumer [Info][consumer::Info], returned by [Stream::consumers].
+#[derive(Debug)]
+pub struct Consumers {
+    paged: Paged<consumer::Info>,
+}
+
+impl futures::Stream for Consumers {
+    type Item = Result<consumer::Info, RequestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.paged.poll_next_unpin(cx)
+    }
 }
 
 /// `StreamConfig` determines the properties for a stream.
@@ -491,6 +697,12 @@ pub struct Config {
     /// Indicates if purges will be denied or not.
     #[serde(default, skip_serializing_if = "is_default")]
     pub deny_purge: bool,
+    /// Allows any replica to answer [Stream::direct_get] requests, not only the leader.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub allow_direct: bool,
+    /// Allows the mirrors of this stream to answer [Stream::direct_get] requests.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub mirror_direct: bool,
 }
 
 impl From<&Config> for Config {
@@ -588,6 +800,53 @@ pub struct DeleteStatus {
     pub success: bool,
 }
 
+/// Selects the messages purged by [Stream::send_purge]. Purges all messages by default.
+#[derive(Debug, Default, Serialize, Clone, PartialEq, Eq)]
+pub struct Purge {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<String>,
+    #[serde(rename = "seq", skip_serializing_if = "Option::is_none")]
+    sequence: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep: Option<u64>,
+}
+
+impl Purge {
+    /// Creates a purge of all messages.
+    pub fn new() -> Purge {
+        Default::default()
+    }
+
+    /// Purges only the messages of the subject, which can contain wildcards.
+    pub fn filter<T: Into<String>>(mut self, subject: T) -> Purge {
+        self.filter = Some(subject.into());
+        self
+    }
+
+    /// Purges messages up to, but not including, the sequence.
+    /// Cannot be combined with [Purge::keep].
+    pub fn sequence(mut self, sequence: u64) -> Purge {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// Keeps the given number of newest messages.
+    /// Cannot be combined with [Purge::sequence].
+    pub fn keep(mut self, keep: u64) -> Purge {
+        self.keep = Some(keep);
+        self
+    }
+}
+
+/// The response to a [Stream::purge].
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeResponse {
+    /// Whether the purge succeeded.
+    pub success: bool,
+    /// The number of purged messages.
+    pub purged: u64,
+}
+
 /// information about the given stream.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct State {
@@ -635,9 +894,77 @@ pub struct RawMessage {
     pub time: time::OffsetDateTime,
 }
 
+/// A stream message returned by the Direct Get API, with its payload and headers decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamMessage {
+    /// Subject of the message.
+    pub subject: String,
+    /// Sequence of the message.
+    pub sequence: u64,
+    /// Headers the message was published with.
+    pub headers: HeaderMap,
+    /// Payload of the message.
+    pub payload: Bytes,
+    /// The time the message was stored.
+    pub time: time::OffsetDateTime,
+}
+
+impl StreamMessage {
+    /// Converts a Direct Get response, whose headers describe the stored message.
+    fn from_direct(message: crate::Message) -> Result<StreamMessage, RequestError> {
+        match message.status {
+            None | Some(StatusCode::OK) => {}
+            Some(status) => {
+                let code = if status == StatusCode::NOT_FOUND {
+                    ErrorCode::NO_MESSAGE_FOUND
+                } else {
+                    ErrorCode::BAD_REQUEST
+                };
+                return Err(RequestError::Api(response::Error {
+                    code: code.0,
+                    status: status.as_u16(),
+                    description: message.description.unwrap_or_default(),
+                }));
+            }
+        }
+
+        let mut headers = message.headers.ok_or(RequestError::InvalidResponse(
+            "direct get response has no headers",
+        ))?;
+        let subject = headers
+            .subject()
+            .ok_or(RequestError::InvalidResponse(
+                "direct get response has no subject",
+            ))?
+            .to_string();
+        let sequence = headers.sequence().ok_or(RequestError::InvalidResponse(
+            "direct get response has no sequence",
+        ))?;
+        let time = headers
+            .time_stamp()
+            .and_then(|time| time::OffsetDateTime::parse(time, &Rfc3339).ok())
+            .ok_or(RequestError::InvalidResponse(
+                "direct get response has no valid time stamp",
+            ))?;
+
+        // what remains are the headers the message was published with.
+        for name in [NATS_STREAM, NATS_SEQUENCE, NATS_SUBJECT, NATS_TIME_STAMP] {
+            headers.remove(name);
+        }
+
+        Ok(StreamMessage {
+            subject,
+            sequence,
+            headers,
+            payload: message.payload,
+            time,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct GetRawMessage {
-    pub message: RawMessage,
+pub(crate) struct GetRawMessage {
+    pub(crate) message: RawMessage,
 }
 
 fn is_default<T: Default + Eq>(t: &T) -> bool {
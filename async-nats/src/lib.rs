@@ -94,19 +94,31 @@
 //! }
 //! #     Ok(())
 //! # }
+//! ```
+//!
+//! ## Features
+//!
+//! - `tracing`: emits connection diagnostics through the [tracing](https://docs.rs/tracing) crate,
+//!   with spans for connecting, reconnecting, publishing, requests and JetStream API calls.
+//!   Without it, the diagnostics are discarded.
+//! - `msgpack`: adds the MessagePack [Codec][crate::codec::Codec] for typed payloads.
+//! - `cbor`: adds the CBOR [Codec][crate::codec::Codec] for typed payloads.
 
 use futures::future::FutureExt;
-use futures::select;
 use futures::stream::Stream;
+use futures::{pin_mut, select};
 
 use core::fmt;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::iter;
+use std::mem;
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::option;
 use std::pin::Pin;
 use std::slice;
 use std::str::{self, FromStr};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::ErrorKind;
 use url::{Host, Url};
@@ -122,6 +134,9 @@ pub type Error = Box<dyn std::error::Error + Send + Sync>;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const LANG: &str = "rust";
+/// Subscription id reserved for the shared response inbox used by requests.
+const MULTIPLEXER_SID: u64 = 0;
+const MULTIPLEXER_SWEEP_MIN: usize = 64;
 
 /// A re-export of the `rustls` crate used in this crate,
 /// for use in cases where manual client configurations
@@ -130,21 +145,64 @@ pub use tokio_rustls::rustls;
 
 use connection::Connection;
 use connector::{Connector, ConnectorOptions};
-pub use header::{HeaderMap, HeaderValue};
+pub use header::{HeaderMap, HeaderName, HeaderValue};
+
+/// Diagnostics are emitted through `tracing` with the `tracing` feature and discarded otherwise.
+macro_rules! log_event {
+    ($level:ident, $($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { log_event!(error, $($arg)*) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { log_event!(warn, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { log_event!(info, $($arg)*) };
+}
+
+/// Records a field declared on the current span.
+macro_rules! record {
+    ($field:literal, $value:expr) => {{
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, &$value);
+    }};
+}
 
 pub(crate) mod auth_utils;
 mod client;
 mod connection;
 mod connector;
+mod handler;
 mod options;
+mod queue;
+mod statistics;
 
 use crate::options::CallbackArg1;
-pub use client::Client;
+pub use client::{
+    Client, DrainError, FlushError, PublishError, Request, RequestError, Responses, SubscribeError,
+    UnsubscribeError,
+};
+pub use handler::Handler;
 pub use options::{AuthError, ConnectOptions};
+pub use queue::SlowConsumerPolicy;
+use statistics::Counters;
+pub use statistics::Statistics;
 
+pub mod backoff;
+pub mod codec;
 pub mod header;
 pub mod jetstream;
 pub mod message;
+pub mod service;
 pub mod status;
 mod tls;
 
@@ -232,11 +290,18 @@ pub enum Command {
         respond: Option<String>,
         headers: Option<HeaderMap>,
     },
+    Request {
+        subject: String,
+        payload: Bytes,
+        respond: String,
+        headers: Option<HeaderMap>,
+        sender: oneshot::Sender<Message>,
+    },
     Subscribe {
         sid: u64,
         subject: String,
         queue_group: Option<String>,
-        sender: mpsc::Sender<Message>,
+        sender: queue::Sender,
     },
     Unsubscribe {
         sid: u64,
@@ -247,6 +312,12 @@ pub enum Command {
         result: oneshot::Sender<Result<(), io::Error>>,
     },
     TryFlush,
+    Drain {
+        sid: u64,
+    },
+    DrainConnection {
+        result: oneshot::Sender<()>,
+    },
     Connect(ConnectInfo),
 }
 
@@ -276,38 +347,153 @@ pub enum ClientOp {
 #[derive(Debug)]
 struct Subscription {
     subject: String,
-    sender: mpsc::Sender<Message>,
+    sender: queue::Sender,
     queue_group: Option<String>,
     delivered: u64,
     max: Option<u64>,
 }
 
+/// A single wildcard subscription shared by all requests, routing each response to the
+/// waiting request by the last token of its reply subject.
+#[derive(Debug)]
+struct Multiplexer {
+    subject: String,
+    prefix: String,
+    senders: HashMap<String, oneshot::Sender<Message>>,
+    /// Number of in-flight requests at which those no longer waiting are removed.
+    sweep_at: usize,
+}
+
+impl Multiplexer {
+    fn insert(&mut self, token: String, sender: oneshot::Sender<Message>) {
+        // forget about requests that timed out or were dropped, doubling the threshold
+        // keeps the cost of sweeping constant per request.
+        if self.senders.len() >= self.sweep_at {
+            self.senders.retain(|_, sender| !sender.is_closed());
+            self.sweep_at = (self.senders.len() * 2).max(MULTIPLEXER_SWEEP_MIN);
+        }
+        self.senders.insert(token, sender);
+    }
+}
+
+/// Subscriptions that were unsubscribed and wait for the `PONG` confirming that the server
+/// delivered all of their messages.
+#[derive(Debug)]
+struct Drain {
+    /// Number of `PONG`s still to be received, including the one for this drain.
+    pongs: usize,
+    sids: Vec<u64>,
+    /// Set if the whole connection is drained, notified once the handler is closed.
+    close: Option<oneshot::Sender<()>>,
+}
+
+/// Commands received while reconnecting, replayed in order once connected again.
+#[derive(Debug)]
+struct ReconnectBuffer {
+    commands: VecDeque<Command>,
+    /// Size of the buffered payloads in bytes.
+    size: usize,
+    max_size: usize,
+    statistics: Arc<Counters>,
+}
+
+impl ReconnectBuffer {
+    fn new(max_size: usize, statistics: Arc<Counters>) -> ReconnectBuffer {
+        ReconnectBuffer {
+            commands: VecDeque::new(),
+            size: 0,
+            max_size,
+            statistics,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.size >= self.max_size
+    }
+
+    fn push(&mut self, command: Command) {
+        match &command {
+            // there is no point in keeping the connection alive while it is down.
+            Command::Ping | Command::TryFlush => return,
+            Command::Publish { payload, .. } | Command::Request { payload, .. } => {
+                self.size += payload.len();
+                self.statistics
+                    .pending_bytes
+                    .fetch_add(payload.len() as u64, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        self.commands.push_back(command);
+    }
+
+    fn take(&mut self) -> VecDeque<Command> {
+        // the replayed payloads are counted again once written.
+        self.statistics
+            .pending_bytes
+            .fetch_sub(self.size as u64, Ordering::Relaxed);
+        self.size = 0;
+        mem::take(&mut self.commands)
+    }
+}
+
 /// A connection handler which facilitates communication from channels to a single shared connection.
 pub(crate) struct ConnectionHandler {
     connection: Connection,
     connector: Connector,
     subscriptions: HashMap<u64, Subscription>,
+    multiplexer: Option<Multiplexer>,
+    drains: VecDeque<Drain>,
+    drained: Option<oneshot::Sender<()>>,
+    closed: bool,
+    disconnected: bool,
+    /// Whether the first connection was established, reconnects are only counted after it.
+    connected: bool,
+    reconnect_buffer: ReconnectBuffer,
     events: mpsc::Sender<Event>,
+    /// Keepalive pings sent since the last `PONG`.
     pending_pings: usize,
     max_pings: usize,
+    /// All pings awaiting their `PONG`, including the ones sent to finish drains.
+    outstanding_pongs: usize,
     info_sender: tokio::sync::watch::Sender<ServerInfo>,
+    state_sender: tokio::sync::watch::Sender<ConnectionState>,
 }
 
 impl ConnectionHandler {
+    /// Creates a handler for an established connection, or a disconnected one that
+    /// connects in [ConnectionHandler::process] if no connection is given.
     pub(crate) fn new(
-        connection: Connection,
+        connection: Option<Connection>,
         connector: Connector,
         events: mpsc::Sender<Event>,
         info_sender: tokio::sync::watch::Sender<ServerInfo>,
+        state_sender: tokio::sync::watch::Sender<ConnectionState>,
+        reconnect_buffer_size: usize,
+        statistics: Arc<Counters>,
     ) -> ConnectionHandler {
+        let connected = connection.is_some();
+        // until the first connection is established nothing is written to the placeholder.
+        let connection = connection.unwrap_or_else(|| {
+            let (stream, _) = tokio::io::duplex(1);
+            Connection::new(Box::new(stream), statistics.clone())
+        });
         ConnectionHandler {
             connection,
             connector,
             subscriptions: HashMap::new(),
+            multiplexer: None,
+            drains: VecDeque::new(),
+            drained: None,
+            closed: false,
+            disconnected: !connected,
+            connected,
+            reconnect_buffer: ReconnectBuffer::new(reconnect_buffer_size, statistics),
             events,
             pending_pings: 0,
             max_pings: 2,
+            outstanding_pongs: 0,
             info_sender,
+            state_sender,
         }
     }
 
@@ -316,11 +502,22 @@ impl ConnectionHandler {
         mut receiver: mpsc::Receiver<Command>,
     ) -> Result<(), io::Error> {
         loop {
+            if self.disconnected {
+                if let Err(err) = self.handle_reconnect(&mut receiver).await {
+                    error!("failed to reconnect: {}", err);
+                    self.closed = true;
+                }
+            }
+
+            if self.closed {
+                break;
+            }
+
             select! {
                 maybe_command = receiver.recv().fuse() => {
                     match maybe_command {
                         Some(command) => if let Err(err) = self.handle_command(command).await {
-                            println!("error handling command {}", err);
+                            error!("error handling command: {}", err);
                         }
                         None => {
                             break;
@@ -331,27 +528,37 @@ impl ConnectionHandler {
                 maybe_op_result = self.connection.read_op().fuse() => {
                     match maybe_op_result {
                         Ok(Some(server_op)) => if let Err(err) = self.handle_server_op(server_op).await {
-                            println!("error handling operation {}", err);
+                            error!("error handling operation: {}", err);
                         }
                         Ok(None) => {
-                            if let Err(err) = self.handle_disconnect().await {
-                                println!("error handling operation {}", err);
-                            } else {
-                            }
+                            self.handle_disconnect();
                         }
                         Err(op_err) => {
-                            if let Err(err) = self.handle_disconnect().await {
-                                println!("error reconnecting {}. original error={}", err, op_err);
-                            }
+                            warn!("error reading from the connection: {}", op_err);
+                            self.handle_disconnect();
                         },
                     }
                 }
             }
+
+            if self.closed {
+                break;
+            }
         }
 
-        self.connection.flush().await?;
+        let result = if self.disconnected {
+            Ok(())
+        } else {
+            self.connection.flush().await
+        };
 
-        Ok(())
+        self.state_sender.send(ConnectionState::Closed).ok();
+        if let Some(drained) = self.drained.take() {
+            drained.send(()).ok();
+        }
+        self.events.send(Event::Closed).await.ok();
+
+        result
     }
 
     async fn handle_server_op(&mut self, server_op: ServerOp) -> Result<(), io::Error> {
@@ -361,7 +568,21 @@ impl ConnectionHandler {
                 self.connection.flush().await?;
             }
             ServerOp::Pong => {
-                self.pending_pings -= 1;
+                // any `PONG` shows that the connection is alive.
+                self.pending_pings = 0;
+                self.outstanding_pongs = self.outstanding_pongs.saturating_sub(1);
+
+                for drain in self.drains.iter_mut() {
+                    drain.pongs -= 1;
+                }
+                while let Some(drain) = self.drains.front() {
+                    if drain.pongs > 0 {
+                        break;
+                    }
+                    if let Some(drain) = self.drains.pop_front() {
+                        self.finish_drain(drain);
+                    }
+                }
             }
             ServerOp::Error(error) => {
                 self.events.try_send(Event::ServerError(error)).ok();
@@ -375,7 +596,28 @@ impl ConnectionHandler {
                 status,
                 description,
             } => {
-                if let Some(subscription) = self.subscriptions.get_mut(&sid) {
+                if sid == MULTIPLEXER_SID {
+                    if let Some(multiplexer) = self.multiplexer.as_mut() {
+                        let maybe_token = subject
+                            .strip_prefix(&multiplexer.prefix)
+                            .map(ToOwned::to_owned);
+                        if let Some(token) = maybe_token {
+                            if let Some(sender) = multiplexer.senders.remove(&token) {
+                                let message = Message {
+                                    subject,
+                                    reply,
+                                    payload,
+                                    headers,
+                                    status,
+                                    description,
+                                };
+
+                                // the request might have timed out in the meantime.
+                                sender.send(message).ok();
+                            }
+                        }
+                    }
+                } else if let Some(subscription) = self.subscriptions.get_mut(&sid) {
                     let message = Message {
                         subject,
                         reply,
@@ -387,8 +629,17 @@ impl ConnectionHandler {
 
                     // if the channel for subscription was dropped, remove the
                     // subscription from the map and unsubscribe.
-                    match subscription.sender.try_send(message) {
-                        Ok(_) => {
+                    match subscription.sender.push(message) {
+                        queue::Push::Delivered {
+                            dropped,
+                            over_limit,
+                        } => {
+                            if dropped > 0 {
+                                self.connection.statistics.add_slow_consumers(sid, dropped);
+                            }
+                            if dropped > 0 || over_limit {
+                                self.events.try_send(Event::SlowConsumer(sid)).ok();
+                            }
                             subscription.delivered += 1;
                             // if this `Subscription` has set `max` value, check if it
                             // was reached. If yes, remove the `Subscription` and in
@@ -399,10 +650,11 @@ impl ConnectionHandler {
                                 }
                             }
                         }
-                        Err(mpsc::error::TrySendError::Full(_)) => {
-                            self.events.send(Event::SlowConsumer(sid)).await.ok();
+                        queue::Push::Dropped => {
+                            self.connection.statistics.add_slow_consumers(sid, 1);
+                            self.events.try_send(Event::SlowConsumer(sid)).ok();
                         }
-                        Err(mpsc::error::TrySendError::Closed(_)) => {
+                        queue::Push::Closed => {
                             self.subscriptions.remove(&sid);
                             self.connection
                                 .write_op(ClientOp::Unsubscribe { sid, max: None })
@@ -412,10 +664,18 @@ impl ConnectionHandler {
                     }
                 }
             }
-            // TODO: we should probably update advertised server list here too.
             ServerOp::Info(info) => {
-                if info.lame_duck_mode {
+                self.connector.add_discovered_servers(&info.connect_urls);
+                let lame_duck_mode = info.lame_duck_mode;
+                self.info_sender.send(*info).ok();
+
+                if lame_duck_mode {
                     self.events.send(Event::LameDuckMode).await.ok();
+                    // migrate to another server before this one shuts down.
+                    if self.connector.lame_duck() {
+                        self.connection.flush().await.ok();
+                        self.handle_disconnect();
+                    }
                 }
             }
 
@@ -428,6 +688,11 @@ impl ConnectionHandler {
     }
 
     async fn handle_command(&mut self, command: Command) -> Result<(), io::Error> {
+        if self.disconnected {
+            self.reconnect_buffer.push(command);
+            return Ok(());
+        }
+
         match command {
             Command::Unsubscribe { sid, max } => {
                 if let Some(subscription) = self.subscriptions.get_mut(&sid) {
@@ -448,7 +713,7 @@ impl ConnectionHandler {
                         .write_op(ClientOp::Unsubscribe { sid, max })
                         .await
                     {
-                        println!("Send failed with {:?}", err);
+                        warn!("sending unsubscribe for sid {} failed: {}", sid, err);
                     }
                 }
             }
@@ -456,26 +721,23 @@ impl ConnectionHandler {
                 self.pending_pings += 1;
 
                 if self.pending_pings > self.max_pings {
-                    self.handle_disconnect().await?;
+                    self.handle_disconnect();
+                    return Ok(());
                 }
 
                 if let Err(_err) = self.connection.write_op(ClientOp::Ping).await {
-                    self.handle_disconnect().await?;
+                    self.handle_disconnect();
+                    return Ok(());
                 }
+                self.outstanding_pongs += 1;
 
                 self.connection.flush().await?;
             }
             Command::Flush { result } => {
                 if let Err(_err) = self.connection.flush().await {
-                    if let Err(err) = self.handle_disconnect().await {
-                        result.send(Err(err)).map_err(|_| {
-                            io::Error::new(io::ErrorKind::Other, "one shot failed to be received")
-                        })?;
-                    } else if let Err(err) = self.connection.flush().await {
-                        result.send(Err(err)).map_err(|_| {
-                            io::Error::new(io::ErrorKind::Other, "one shot failed to be received")
-                        })?;
-                    }
+                    // flush again once reconnected.
+                    self.handle_disconnect();
+                    self.reconnect_buffer.push(Command::Flush { result });
                 } else {
                     result.send(Ok(())).map_err(|_| {
                         io::Error::new(io::ErrorKind::Other, "one shot failed to be received")
@@ -485,6 +747,81 @@ impl ConnectionHandler {
             Command::TryFlush => {
                 self.connection.flush().await?;
             }
+            Command::Drain { sid } => {
+                if self.subscriptions.contains_key(&sid) {
+                    self.start_drain(vec![sid], None).await;
+                }
+            }
+            Command::DrainConnection { result } => {
+                self.state_sender.send(ConnectionState::Draining).ok();
+                let mut sids: Vec<u64> = self.subscriptions.keys().copied().collect();
+                if self.multiplexer.is_some() {
+                    sids.push(MULTIPLEXER_SID);
+                }
+                self.start_drain(sids, Some(result)).await;
+            }
+            Command::Request {
+                subject,
+                payload,
+                respond,
+                headers,
+                sender,
+            } => {
+                let (prefix, token) = respond.rsplit_once('.').ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "malformed request subject")
+                })?;
+
+                let multiplexer = if let Some(multiplexer) = self.multiplexer.as_mut() {
+                    multiplexer
+                } else {
+                    let subject = format!("{}.*", prefix);
+
+                    if let Err(err) = self
+                        .connection
+                        .write_op(ClientOp::Subscribe {
+                            sid: MULTIPLEXER_SID,
+                            subject: subject.clone(),
+                            queue_group: None,
+                        })
+                        .await
+                    {
+                        warn!("sending subscribe for the request inbox failed: {}", err);
+                    }
+
+                    self.multiplexer.insert(Multiplexer {
+                        subject,
+                        prefix: format!("{}.", prefix),
+                        senders: HashMap::new(),
+                        sweep_at: MULTIPLEXER_SWEEP_MIN,
+                    })
+                };
+                multiplexer.insert(token.to_owned(), sender);
+
+                // the request is flushed right away, so callers don't wait for a flush.
+                let written = match self
+                    .connection
+                    .write_op(ClientOp::Publish {
+                        subject: subject.clone(),
+                        payload: payload.clone(),
+                        respond: Some(respond.clone()),
+                        headers: headers.clone(),
+                    })
+                    .await
+                {
+                    Ok(()) => self.connection.flush().await,
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = written {
+                    warn!("sending publish on {} failed: {}", subject, err);
+                    self.handle_disconnect();
+                    self.reconnect_buffer.push(Command::Publish {
+                        subject,
+                        payload,
+                        respond: Some(respond),
+                        headers,
+                    });
+                }
+            }
             Command::Subscribe {
                 sid,
                 subject,
@@ -510,7 +847,7 @@ impl ConnectionHandler {
                     })
                     .await
                 {
-                    println!("Sending Subscribe failed with {:?}", err);
+                    warn!("sending subscribe for sid {} failed: {}", sid, err);
                 }
             }
             Command::Publish {
@@ -519,7 +856,7 @@ impl ConnectionHandler {
                 respond,
                 headers,
             } => {
-                while let Err(err) = self
+                if let Err(err) = self
                     .connection
                     .write_op(ClientOp::Publish {
                         subject: subject.clone(),
@@ -529,17 +866,23 @@ impl ConnectionHandler {
                     })
                     .await
                 {
-                    self.handle_disconnect().await?;
-                    println!("Sending Publish failed with {:?}", err);
+                    warn!("sending publish on {} failed: {}", subject, err);
+                    self.handle_disconnect();
+                    self.reconnect_buffer.push(Command::Publish {
+                        subject,
+                        payload,
+                        respond,
+                        headers,
+                    });
                 }
             }
             Command::Connect(connect_info) => {
-                while let Err(_err) = self
+                if let Err(_err) = self
                     .connection
-                    .write_op(ClientOp::Connect(connect_info.clone()))
+                    .write_op(ClientOp::Connect(connect_info))
                     .await
                 {
-                    self.handle_disconnect().await?;
+                    self.handle_disconnect();
                 }
             }
         }
@@ -547,16 +890,101 @@ impl ConnectionHandler {
         Ok(())
     }
 
-    async fn handle_disconnect(&mut self) -> io::Result<()> {
-        self.events.try_send(Event::Disconnect).ok();
-        self.handle_reconnect().await?;
+    /// Unsubscribes the given subscriptions and sends a `PING`. Subscriptions are removed once
+    /// the matching `PONG` arrives, as all their messages were delivered by then.
+    /// If the connection is lost in the meantime, the drain finishes on reconnect.
+    async fn start_drain(&mut self, sids: Vec<u64>, close: Option<oneshot::Sender<()>>) {
+        let result = async {
+            for sid in &sids {
+                self.connection
+                    .write_op(ClientOp::Unsubscribe {
+                        sid: *sid,
+                        max: None,
+                    })
+                    .await?;
+            }
+            self.connection.write_op(ClientOp::Ping).await?;
+            self.connection.flush().await
+        }
+        .await;
+
+        self.outstanding_pongs += 1;
+        self.drains.push_back(Drain {
+            pongs: self.outstanding_pongs,
+            sids,
+            close,
+        });
+
+        if let Err(err) = result {
+            warn!("sending unsubscribe for drain failed: {}", err);
+            self.handle_disconnect();
+        }
+    }
+
+    fn finish_drain(&mut self, drain: Drain) {
+        for sid in drain.sids {
+            if sid == MULTIPLEXER_SID {
+                self.multiplexer = None;
+            } else {
+                self.subscriptions.remove(&sid);
+            }
+        }
 
-        Ok(())
+        if drain.close.is_some() {
+            self.drained = drain.close;
+            self.closed = true;
+        }
+    }
+
+    /// Marks the connection as lost. Reconnecting starts in the next iteration of
+    /// [ConnectionHandler::process], until then all commands are buffered.
+    fn handle_disconnect(&mut self) {
+        if !self.disconnected {
+            self.disconnected = true;
+            self.state_sender.send(ConnectionState::Disconnected).ok();
+            self.connection.discard_pending();
+            self.events.try_send(Event::Disconnect).ok();
+        }
     }
 
-    async fn handle_reconnect(&mut self) -> Result<(), io::Error> {
-        let (info, connection) = self.connector.connect().await?;
+    /// Reconnects while buffering the incoming commands, which are replayed once connected.
+    /// Fails if the maximum number of reconnect attempts was reached.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "reconnect", skip_all))]
+    async fn handle_reconnect(
+        &mut self,
+        receiver: &mut mpsc::Receiver<Command>,
+    ) -> Result<(), io::Error> {
+        let (info, connection) = {
+            let connect = self.connector.connect().fuse();
+            pin_mut!(connect);
+
+            loop {
+                // stop taking commands from clients once the buffer is full.
+                if self.reconnect_buffer.is_full() {
+                    break connect.await?;
+                }
+
+                select! {
+                    result = connect => break result?,
+                    maybe_command = receiver.recv().fuse() => match maybe_command {
+                        Some(command) => self.reconnect_buffer.push(command),
+                        None => break connect.await?,
+                    },
+                }
+            }
+        };
+
         self.connection = connection;
+        self.disconnected = false;
+        self.state_sender.send(ConnectionState::Connected).ok();
+        let reconnected = self.connected;
+        self.connected = true;
+        if reconnected {
+            self.connection
+                .statistics
+                .reconnects
+                .fetch_add(1, Ordering::Relaxed);
+        }
         self.info_sender.send(info).map_err(|err| {
             std::io::Error::new(
                 ErrorKind::Other,
@@ -564,9 +992,36 @@ impl ConnectionHandler {
             )
         })?;
 
+        // pings sent on the old connection will never be answered, so there is nothing left
+        // to wait for before finishing pending drains.
+        self.pending_pings = 0;
+        self.outstanding_pongs = 0;
+        while let Some(drain) = self.drains.pop_front() {
+            self.finish_drain(drain);
+        }
+
         self.subscriptions
             .retain(|_, subscription| !subscription.sender.is_closed());
 
+        if let Err(err) = self.resubscribe().await {
+            warn!("resubscribing failed: {}", err);
+            self.handle_disconnect();
+            return Ok(());
+        }
+        if reconnected {
+            self.events.try_send(Event::Reconnect).ok();
+        }
+
+        for command in self.reconnect_buffer.take() {
+            if let Err(err) = self.handle_command(command).await {
+                error!("error handling command: {}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn resubscribe(&mut self) -> io::Result<()> {
         for (sid, subscription) in &self.subscriptions {
             self.connection
                 .write_op(ClientOp::Subscribe {
@@ -574,12 +1029,29 @@ impl ConnectionHandler {
                     subject: subscription.subject.to_owned(),
                     queue_group: subscription.queue_group.to_owned(),
                 })
-                .await
-                .unwrap();
+                .await?;
+
+            if let Some(max) = subscription.max {
+                self.connection
+                    .write_op(ClientOp::Unsubscribe {
+                        sid: *sid,
+                        max: Some(max - subscription.delivered),
+                    })
+                    .await?;
+            }
         }
-        self.events.try_send(Event::Reconnect).ok();
 
-        Ok(())
+        if let Some(multiplexer) = &self.multiplexer {
+            self.connection
+                .write_op(ClientOp::Subscribe {
+                    sid: MULTIPLEXER_SID,
+                    subject: multiplexer.subject.to_owned(),
+                    queue_group: None,
+                })
+                .await?;
+        }
+
+        self.connection.flush().await
     }
 }
 
@@ -606,6 +1078,7 @@ pub async fn connect_with_options<A: ToServerAddrs>(
 
     let (events_tx, mut events_rx) = mpsc::channel(128);
 
+    let statistics = Arc::new(Counters::default());
     let mut connector = Connector::new(
         addrs,
         ConnectorOptions {
@@ -616,25 +1089,49 @@ pub async fn connect_with_options<A: ToServerAddrs>(
             tls_client_config: options.tls_client_config,
             auth: options.auth,
             no_echo: options.no_echo,
+            max_reconnects: options.max_reconnects,
+            reconnect_delay_callback: options.reconnect_delay_callback,
+            retain_servers_order: options.retain_servers_order,
+            ignore_discovered_servers: options.ignore_discovered_servers,
         },
         events_tx.clone(),
+        statistics.clone(),
     )?;
 
-    let (info, connection) = connector.try_connect().await?;
-
-    let (info_sender, info_watcher) = tokio::sync::watch::channel(info);
+    let reconnect_buffer_size = options.reconnect_buffer_size;
+    let (info, connection) = match connector.try_connect().await {
+        Ok((info, connection)) => (Some(info), Some(connection)),
+        Err(err) if options.retry_on_failed_connect => {
+            events_tx
+                .try_send(Event::ClientError(ClientError::Other(err.to_string())))
+                .ok();
+            (None, None)
+        }
+        Err(err) => return Err(err),
+    };
 
-    let mut connection_handler =
-        ConnectionHandler::new(connection, connector, events_tx, info_sender);
+    let (state_sender, state_watcher) = tokio::sync::watch::channel(if connection.is_some() {
+        ConnectionState::Connected
+    } else {
+        ConnectionState::Connecting
+    });
+    let (info_sender, info_watcher) = tokio::sync::watch::channel(info.unwrap_or_default());
 
     // TODO make channel size configurable
     let (sender, receiver) = mpsc::channel(options.sender_capacity);
 
     let client = Client::new(
         info_watcher,
+        state_watcher,
         sender.clone(),
-        options.subscription_capacity,
+        queue::Limits {
+            messages: options.subscription_capacity,
+            bytes: options.subscription_pending_bytes,
+            policy: options.slow_consumer_policy,
+        },
         options.inbox_prefix,
+        options.request_timeout,
+        statistics.clone(),
     );
     tokio::spawn({
         let sender = sender.clone();
@@ -665,11 +1162,52 @@ pub async fn connect_with_options<A: ToServerAddrs>(
         }
     });
 
-    task::spawn(async move { connection_handler.process(receiver).await });
+    task::spawn(async move {
+        // with `retry_on_failed_connect` the handler starts disconnected and establishes the
+        // first connection in the background, buffering commands like during a reconnect.
+        let mut connection_handler = ConnectionHandler::new(
+            connection,
+            connector,
+            events_tx,
+            info_sender,
+            state_sender,
+            reconnect_buffer_size,
+            statistics,
+        );
+        connection_handler.process(receiver).await
+    });
 
     Ok(client)
 }
 
+/// State of the connection to the server, as returned by
+/// [Client::connection_state][crate::Client::connection_state].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The initial connection is being established in the background, see
+    /// [ConnectOptions::retry_on_failed_connect].
+    Connecting,
+    Connected,
+    /// The connection was lost and the client is reconnecting.
+    Disconnected,
+    /// The client is draining and closes once all messages were delivered.
+    Draining,
+    /// The client was drained or gave up reconnecting and can no longer be used.
+    Closed,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Disconnected => write!(f, "disconnected"),
+            ConnectionState::Draining => write!(f, "draining"),
+            ConnectionState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
     Reconnect,
@@ -678,6 +1216,8 @@ pub enum Event {
     SlowConsumer(u64),
     ServerError(ServerError),
     ClientError(ClientError),
+    /// The client gave up reconnecting or was drained and will not deliver any more messages.
+    Closed,
 }
 
 impl fmt::Display for Event {
@@ -689,6 +1229,7 @@ impl fmt::Display for Event {
             Event::SlowConsumer(sid) => write!(f, "slow consumers for subscription {}", sid),
             Event::ServerError(err) => write!(f, "server error: {}", err),
             Event::ClientError(err) => write!(f, "client error: {}", err),
+            Event::Closed => write!(f, "connection closed"),
         }
     }
 }
@@ -727,16 +1268,12 @@ pub async fn connect<A: ToServerAddrs>(addrs: A) -> Result<Client, io::Error> {
 /// ```
 pub struct Subscriber {
     sid: u64,
-    receiver: mpsc::Receiver<Message>,
+    receiver: queue::Receiver,
     sender: mpsc::Sender<Command>,
 }
 
 impl Subscriber {
-    fn new(
-        sid: u64,
-        sender: mpsc::Sender<Command>,
-        receiver: mpsc::Receiver<Message>,
-    ) -> Subscriber {
+    fn new(sid: u64, sender: mpsc::Sender<Command>, receiver: queue::Receiver) -> Subscriber {
         Subscriber {
             sid,
             sender,
@@ -757,14 +1294,13 @@ impl Subscriber {
     ///  subscriber.unsubscribe().await?;
     /// # Ok(())
     /// # }
-    pub async fn unsubscribe(&mut self) -> io::Result<()> {
+    pub async fn unsubscribe(&mut self) -> Result<(), UnsubscribeError> {
         self.sender
             .send(Command::Unsubscribe {
                 sid: self.sid,
                 max: None,
             })
-            .await
-            .map_err(|err| io::Error::new(ErrorKind::Other, err))?;
+            .await?;
         self.receiver.close();
         Ok(())
     }
@@ -794,16 +1330,99 @@ impl Subscriber {
     /// println!("no more messages, unsubscribed");
     /// # Ok(())
     /// # }
-    pub async fn unsubscribe_after(&mut self, unsub_after: u64) -> io::Result<()> {
+    pub async fn unsubscribe_after(&mut self, unsub_after: u64) -> Result<(), UnsubscribeError> {
         self.sender
             .send(Command::Unsubscribe {
                 sid: self.sid,
                 max: Some(unsub_after),
             })
+            .await?;
+        Ok(())
+    }
+
+    /// Unsubscribes from the subscription, while still delivering all messages the server
+    /// sent before processing the unsubscribe. The [Subscriber] stream ends after the last
+    /// of those messages.
+    ///
+    /// # Examples
+    /// ```
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let mut subscriber = client.subscribe("foo".into()).await?;
+    /// subscriber.drain().await?;
+    ///
+    /// while let Some(message) = subscriber.next().await {
+    ///     println!("message received: {:?}", message);
+    /// }
+    /// println!("subscription drained");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn drain(&mut self) -> Result<(), DrainError> {
+        self.sender
+            .send(Command::Drain { sid: self.sid })
             .await
-            .map_err(|err| io::Error::new(ErrorKind::Other, err))?;
+            .map_err(|_| DrainError::new())?;
         Ok(())
     }
+
+    /// Sets the limits of messages and bytes pending delivery to this subscriber.
+    /// Once either of them is reached, arriving messages are handled according to the
+    /// [SlowConsumerPolicy].
+    /// Default is set to [ConnectOptions::subscription_capacity] messages and 64 MiB.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let subscriber = client.subscribe("foo".into()).await?;
+    /// subscriber.set_pending_limits(128, 1024 * 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_pending_limits(&self, messages: usize, bytes: usize) {
+        self.receiver.set_limits(messages, bytes);
+    }
+
+    /// Sets how messages are handled once the pending limits are reached.
+    /// Default is set with [ConnectOptions::slow_consumer_policy].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let subscriber = client.subscribe("foo".into()).await?;
+    /// subscriber.set_slow_consumer_policy(async_nats::SlowConsumerPolicy::DropOldest);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_slow_consumer_policy(&self, policy: SlowConsumerPolicy) {
+        self.receiver.set_policy(policy);
+    }
+
+    /// Returns the number of messages dropped because this subscriber was too slow.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    ///
+    /// let subscriber = client.subscribe("foo".into()).await?;
+    /// println!("dropped {} messages", subscriber.dropped_messages());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dropped_messages(&self) -> u64 {
+        self.receiver.dropped()
+    }
 }
 
 impl Drop for Subscriber {
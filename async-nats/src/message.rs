@@ -14,7 +14,10 @@
 //! A Core NATS message.
 use crate::header::HeaderMap;
 use crate::status::StatusCode;
+use crate::{Client, PublishError};
 use bytes::Bytes;
+use std::error;
+use std::fmt;
 
 /// A Core NATS message.
 #[derive(Debug)]
@@ -26,10 +29,102 @@ pub struct Message {
     pub reply: Option<String>,
     /// Payload of the message. Can be any arbitrary data format.
     pub payload: Bytes,
-    /// Optional headers. Rust client uses [http::header].
+    /// Optional [headers][crate::header].
     pub headers: Option<HeaderMap>,
     /// Optional Status of the message. Used mostly for internal handling.
     pub status: Option<StatusCode>,
     /// Optional [status][crate::Message::status] description.
     pub description: Option<String>,
 }
+
+impl Message {
+    /// Publishes the payload to the [reply][Message::reply] subject of the message.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut subscriber = client.subscribe("service".into()).await?;
+    /// while let Some(message) = subscriber.next().await {
+    ///     message.respond(&client, "pong".into()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn respond(&self, client: &Client, payload: Bytes) -> Result<(), RespondError> {
+        let reply = self.reply.clone().ok_or(RespondError::NoReplySubject)?;
+        client.publish(reply, payload).await?;
+        Ok(())
+    }
+
+    /// Publishes the headers and payload to the [reply][Message::reply] subject of the message.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    ///
+    /// let client = async_nats::connect("demo.nats.io").await?;
+    /// let mut subscriber = client.subscribe("service".into()).await?;
+    /// while let Some(message) = subscriber.next().await {
+    ///     let mut headers = async_nats::HeaderMap::new();
+    ///     headers.insert("X-Handled-By", "worker-1".parse()?);
+    ///     message
+    ///         .respond_with_headers(&client, headers, "pong".into())
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn respond_with_headers(
+        &self,
+        client: &Client,
+        headers: HeaderMap,
+        payload: Bytes,
+    ) -> Result<(), RespondError> {
+        let reply = self.reply.clone().ok_or(RespondError::NoReplySubject)?;
+        client.publish_with_headers(reply, headers, payload).await?;
+        Ok(())
+    }
+}
+
+/// An error returned from the [`Message::respond`] or [`Message::respond_with_headers`]
+/// functions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RespondError {
+    /// The message has no reply subject.
+    NoReplySubject,
+    /// Publishing the response failed.
+    Publish(PublishError),
+}
+
+impl fmt::Display for RespondError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RespondError::NoReplySubject => write!(f, "nats: message has no reply subject"),
+            RespondError::Publish(err) => write!(f, "nats: failed to publish response: {}", err),
+        }
+    }
+}
+
+impl error::Error for RespondError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RespondError::Publish(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PublishError> for RespondError {
+    fn from(err: PublishError) -> Self {
+        RespondError::Publish(err)
+    }
+}
@@ -11,7 +11,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Authorization, Client, Event, ToServerAddrs};
+use crate::{backoff, Authorization, Client, Event, SlowConsumerPolicy, ToServerAddrs};
 use futures::Future;
 use std::fmt::Formatter;
 use std::{fmt, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
@@ -38,6 +38,9 @@ pub struct ConnectOptions {
     pub(crate) retry_on_failed_connect: bool,
     pub(crate) max_reconnects: Option<usize>,
     pub(crate) reconnect_buffer_size: usize,
+    pub(crate) reconnect_delay_callback: ReconnectDelayCallback,
+    pub(crate) retain_servers_order: bool,
+    pub(crate) ignore_discovered_servers: bool,
     pub(crate) auth: Authorization,
     pub(crate) tls_required: bool,
     pub(crate) certificates: Vec<PathBuf>,
@@ -47,9 +50,12 @@ pub struct ConnectOptions {
     pub(crate) flush_interval: Duration,
     pub(crate) ping_interval: Duration,
     pub(crate) subscription_capacity: usize,
+    pub(crate) subscription_pending_bytes: usize,
+    pub(crate) slow_consumer_policy: SlowConsumerPolicy,
     pub(crate) sender_capacity: usize,
     pub(crate) event_callback: CallbackArg1<Event, ()>,
     pub(crate) inbox_prefix: String,
+    pub(crate) request_timeout: Option<Duration>,
 }
 
 impl fmt::Debug for ConnectOptions {
@@ -60,6 +66,12 @@ impl fmt::Debug for ConnectOptions {
             .entry(&"retry_on_failed_connect", &self.retry_on_failed_connect)
             .entry(&"reconnect_buffer_size", &self.reconnect_buffer_size)
             .entry(&"max_reconnects", &self.max_reconnects)
+            .entry(&"reconnect_delay_callback", &"set")
+            .entry(&"retain_servers_order", &self.retain_servers_order)
+            .entry(
+                &"ignore_discovered_servers",
+                &self.ignore_discovered_servers,
+            )
             .entry(&"tls_required", &self.tls_required)
             .entry(&"certificates", &self.certificates)
             .entry(&"client_cert", &self.client_cert)
@@ -68,7 +80,14 @@ impl fmt::Debug for ConnectOptions {
             .entry(&"flush_interval", &self.flush_interval)
             .entry(&"ping_interval", &self.ping_interval)
             .entry(&"sender_capacity", &self.sender_capacity)
+            .entry(&"subscription_capacity", &self.subscription_capacity)
+            .entry(
+                &"subscription_pending_bytes",
+                &self.subscription_pending_bytes,
+            )
+            .entry(&"slow_consumer_policy", &self.slow_consumer_policy)
             .entry(&"inbox_prefix", &self.inbox_prefix)
+            .entry(&"request_timeout", &self.request_timeout)
             .finish()
     }
 }
@@ -81,6 +100,9 @@ impl Default for ConnectOptions {
             retry_on_failed_connect: false,
             reconnect_buffer_size: 8 * 1024 * 1024,
             max_reconnects: Some(60),
+            reconnect_delay_callback: ReconnectDelayCallback(Box::new(backoff::full_jitter)),
+            retain_servers_order: false,
+            ignore_discovered_servers: false,
             auth: Authorization::None,
             tls_required: false,
             certificates: Vec::new(),
@@ -91,12 +113,19 @@ impl Default for ConnectOptions {
             ping_interval: Duration::from_secs(60),
             sender_capacity: 128,
             subscription_capacity: 1024,
-            event_callback: CallbackArg1::<Event, ()>(Box::new(move |error| {
+            subscription_pending_bytes: 64 * 1024 * 1024,
+            slow_consumer_policy: SlowConsumerPolicy::default(),
+            event_callback: CallbackArg1::<Event, ()>(Box::new(move |event| {
                 Box::pin(async move {
-                    println!("error : {}", error);
+                    match event {
+                        Event::ServerError(_) | Event::ClientError(_) => error!("{}", event),
+                        Event::Reconnect => info!("{}", event),
+                        _ => warn!("{}", event),
+                    }
                 })
             })),
             inbox_prefix: "_INBOX".to_string(),
+            request_timeout: Some(Duration::from_secs(10)),
         }
     }
 }
@@ -385,7 +414,7 @@ impl ConnectOptions {
     }
 
     /// Sets the capacity for `Subscribers`. Exceeding it will trigger `slow consumer` error
-    /// callback and drop messages.
+    /// callback and handle messages according to the [SlowConsumerPolicy].
     /// Defualt is set to 1024 messages buffer.
     ///
     /// # Examples
@@ -401,6 +430,44 @@ impl ConnectOptions {
         self
     }
 
+    /// Sets the size in bytes of message payloads pending delivery to a `Subscriber`.
+    /// Exceeding it has the same effect as exceeding the
+    /// [subscription_capacity][ConnectOptions::subscription_capacity].
+    /// Default is set to 64 MiB.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new().subscription_pending_bytes(1024 * 1024).connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscription_pending_bytes(mut self, bytes: usize) -> ConnectOptions {
+        self.subscription_pending_bytes = bytes;
+        self
+    }
+
+    /// Sets how messages for a slow `Subscriber` are handled once its pending limits are
+    /// reached. Can be changed per subscriber with
+    /// [Subscriber::set_slow_consumer_policy][crate::Subscriber::set_slow_consumer_policy].
+    /// Default is set to [SlowConsumerPolicy::DropNewest].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new()
+    ///     .slow_consumer_policy(async_nats::SlowConsumerPolicy::DropOldest)
+    ///     .connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slow_consumer_policy(mut self, policy: SlowConsumerPolicy) -> ConnectOptions {
+        self.slow_consumer_policy = policy;
+        self
+    }
+
     /// Registers asynchronous callback for errors that are receiver over the wire from the server.
     ///
     /// # Examples
@@ -495,6 +562,140 @@ impl ConnectOptions {
         self.inbox_prefix = prefix.to_string();
         self
     }
+
+    /// Sets the default timeout for requests made with [Client::request][crate::Client::request].
+    /// `None` means requests wait for a response indefinitely.
+    /// Default is set to 10 seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// async_nats::ConnectOptions::new()
+    ///     .request_timeout(Some(std::time::Duration::from_secs(3)))
+    ///     .connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_timeout(mut self, timeout: Option<Duration>) -> ConnectOptions {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Sets how many times in a row the client tries to reconnect before it gives up and emits
+    /// [Event::Closed]. `None` means it never gives up.
+    /// Default is set to 60 attempts.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new().max_reconnects(Some(10)).connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_reconnects(mut self, max_reconnects: Option<usize>) -> ConnectOptions {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Returns a disconnected client instead of an error if the initial connect fails.
+    /// The client then keeps connecting in the background, buffering published messages
+    /// up to [ConnectOptions::reconnect_buffer_size] until connected.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new().retry_on_failed_connect().connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retry_on_failed_connect(mut self) -> ConnectOptions {
+        self.retry_on_failed_connect = true;
+        self
+    }
+
+    /// Sets the size in bytes of published payloads buffered while reconnecting.
+    /// Once it is full, publishing waits until the client is connected again.
+    /// Default is set to 8 MiB.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new().reconnect_buffer_size(1024 * 1024).connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reconnect_buffer_size(mut self, size: usize) -> ConnectOptions {
+        self.reconnect_buffer_size = size;
+        self
+    }
+
+    /// Sets a callback calculating how long to wait before the next attempt to connect to
+    /// a server. It takes the number of failed attempts for that server, which is `0` for
+    /// the first attempt.
+    ///
+    /// It is recommended to add some random jitter, so that many clients do not reconnect to
+    /// a restarted server at once. See [backoff][crate::backoff] for built-in strategies.
+    /// Default is set to [backoff::full_jitter][crate::backoff::full_jitter].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// # use std::time::Duration;
+    /// async_nats::ConnectOptions::new()
+    ///     .reconnect_delay_callback(|c| Duration::from_millis(std::cmp::min((c * 100) as u64, 8000)))
+    ///     .connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reconnect_delay_callback<F>(mut self, cb: F) -> ConnectOptions
+    where
+        F: Fn(usize) -> Duration + Send + Sync + 'static,
+    {
+        self.reconnect_delay_callback = ReconnectDelayCallback(Box::new(cb));
+        self
+    }
+
+    /// Connects to servers in the order they were given, instead of shuffling the server list
+    /// before each round of connect attempts.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new()
+    ///     .retain_servers_order()
+    ///     .connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain_servers_order(mut self) -> ConnectOptions {
+        self.retain_servers_order = true;
+        self
+    }
+
+    /// Connects only to the servers given on connect, ignoring the other cluster members
+    /// advertised by the server.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> std::io::Result<()> {
+    /// async_nats::ConnectOptions::new()
+    ///     .ignore_discovered_servers()
+    ///     .connect("demo.nats.io").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ignore_discovered_servers(mut self) -> ConnectOptions {
+        self.ignore_discovered_servers = true;
+        self
+    }
 }
 
 type AsyncCallbackArg1<A, T> =
@@ -508,6 +709,14 @@ impl<A, T> CallbackArg1<A, T> {
     }
 }
 
+pub(crate) struct ReconnectDelayCallback(Box<dyn Fn(usize) -> Duration + Send + Sync + 'static>);
+
+impl ReconnectDelayCallback {
+    pub(crate) fn call(&self, attempts: usize) -> Duration {
+        (self.0)(attempts)
+    }
+}
+
 impl<A, T> fmt::Debug for CallbackArg1<A, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.write_str("callback")
@@ -0,0 +1,341 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Queue of messages pending delivery to a [Subscriber][crate::Subscriber], bounded by
+//! message and byte limits.
+
+use crate::Message;
+use futures::task::AtomicWaker;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// What happens to a message arriving for a subscription which reached its pending limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlowConsumerPolicy {
+    /// Drops the arriving message.
+    DropNewest,
+    /// Drops the oldest pending messages to make room for the arriving one.
+    DropOldest,
+    /// Queues the arriving message anyway, so no message is ever dropped.
+    ///
+    /// **Warning:** the pending limits are ignored, and the memory used by a subscriber
+    /// that never catches up grows without bound. Reaching the limits is still reported
+    /// as [Event::SlowConsumer][crate::Event::SlowConsumer].
+    Unbounded,
+}
+
+impl Default for SlowConsumerPolicy {
+    fn default() -> SlowConsumerPolicy {
+        SlowConsumerPolicy::DropNewest
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) messages: usize,
+    pub(crate) bytes: usize,
+    pub(crate) policy: SlowConsumerPolicy,
+}
+
+#[derive(Debug)]
+struct State {
+    messages: VecDeque<Message>,
+    bytes: usize,
+    limits: Limits,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+impl State {
+    fn is_full(&self, size: usize) -> bool {
+        !self.messages.is_empty()
+            && (self.messages.len() >= self.limits.messages
+                || self.bytes + size > self.limits.bytes)
+    }
+
+    fn pop(&mut self) -> Option<Message> {
+        let message = self.messages.pop_front()?;
+        self.bytes -= message.payload.len();
+        Some(message)
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    receiver_waker: AtomicWaker,
+    dropped: AtomicU64,
+}
+
+/// The outcome of [Sender::push].
+#[derive(Debug)]
+pub(crate) enum Push {
+    /// The message was queued, after dropping the given number of the oldest messages,
+    /// or beyond the pending limits.
+    Delivered { dropped: usize, over_limit: bool },
+    /// The message was dropped.
+    Dropped,
+    /// The receiver was closed.
+    Closed,
+}
+
+pub(crate) fn channel(limits: Limits) -> (Sender, Receiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            messages: VecDeque::new(),
+            bytes: 0,
+            limits,
+            sender_closed: false,
+            receiver_closed: false,
+        }),
+        receiver_waker: AtomicWaker::new(),
+        dropped: AtomicU64::new(0),
+    });
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Queues messages, held by the connection handler.
+#[derive(Debug)]
+pub struct Sender {
+    shared: Arc<Shared>,
+}
+
+impl Sender {
+    pub(crate) fn push(&self, message: Message) -> Push {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.receiver_closed {
+            return Push::Closed;
+        }
+
+        let size = message.payload.len();
+        let mut dropped = 0;
+        let mut over_limit = false;
+        if state.is_full(size) {
+            match state.limits.policy {
+                SlowConsumerPolicy::DropNewest => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Push::Dropped;
+                }
+                SlowConsumerPolicy::DropOldest => {
+                    while state.is_full(size) {
+                        state.pop();
+                        dropped += 1;
+                    }
+                    self.shared
+                        .dropped
+                        .fetch_add(dropped as u64, Ordering::Relaxed);
+                }
+                SlowConsumerPolicy::Unbounded => over_limit = true,
+            }
+        }
+
+        state.bytes += size;
+        state.messages.push_back(message);
+        drop(state);
+        self.shared.receiver_waker.wake();
+
+        Push::Delivered {
+            dropped,
+            over_limit,
+        }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.state.lock().unwrap().receiver_closed
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().sender_closed = true;
+        self.shared.receiver_waker.wake();
+    }
+}
+
+/// Receives queued messages, held by the [Subscriber][crate::Subscriber].
+#[derive(Debug)]
+pub(crate) struct Receiver {
+    shared: Arc<Shared>,
+}
+
+impl Receiver {
+    pub(crate) fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Message>> {
+        self.shared.receiver_waker.register(cx.waker());
+
+        let mut state = self.shared.state.lock().unwrap();
+        match state.pop() {
+            Some(message) => Poll::Ready(Some(message)),
+            None if state.sender_closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Stops queueing new messages, while the already queued ones can still be received.
+    pub(crate) fn close(&self) {
+        self.shared.state.lock().unwrap().receiver_closed = true;
+    }
+
+    pub(crate) fn set_limits(&self, messages: usize, bytes: usize) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.limits.messages = messages;
+        state.limits.bytes = bytes;
+    }
+
+    pub(crate) fn set_policy(&self, policy: SlowConsumerPolicy) {
+        self.shared.state.lock().unwrap().limits.policy = policy;
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker_ref;
+
+    fn message(payload: &'static str) -> Message {
+        Message {
+            subject: "test".into(),
+            reply: None,
+            payload: payload.into(),
+            headers: None,
+            status: None,
+            description: None,
+        }
+    }
+
+    fn recv(receiver: &mut Receiver) -> Option<Message> {
+        match receiver.poll_recv(&mut Context::from_waker(noop_waker_ref())) {
+            Poll::Ready(message) => message,
+            Poll::Pending => panic!("no message queued"),
+        }
+    }
+
+    fn limits(policy: SlowConsumerPolicy) -> Limits {
+        Limits {
+            messages: 2,
+            bytes: 1024,
+            policy,
+        }
+    }
+
+    #[test]
+    fn drop_newest() {
+        let (sender, mut receiver) = channel(limits(SlowConsumerPolicy::DropNewest));
+        for payload in ["1", "2", "3"] {
+            sender.push(message(payload));
+        }
+
+        assert_eq!(receiver.dropped(), 1);
+        assert_eq!(recv(&mut receiver).unwrap().payload, "1");
+        assert_eq!(recv(&mut receiver).unwrap().payload, "2");
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let (sender, mut receiver) = channel(limits(SlowConsumerPolicy::DropOldest));
+        for payload in ["1", "2", "3"] {
+            sender.push(message(payload));
+        }
+
+        assert_eq!(receiver.dropped(), 1);
+        assert_eq!(recv(&mut receiver).unwrap().payload, "2");
+        assert_eq!(recv(&mut receiver).unwrap().payload, "3");
+    }
+
+    #[test]
+    fn byte_limit() {
+        let (sender, mut receiver) = channel(limits(SlowConsumerPolicy::Unbounded));
+        receiver.set_limits(10, 4);
+
+        assert!(matches!(
+            sender.push(message("123")),
+            Push::Delivered { .. }
+        ));
+        assert!(matches!(
+            sender.push(message("45")),
+            Push::Delivered {
+                over_limit: true,
+                ..
+            }
+        ));
+
+        recv(&mut receiver);
+        recv(&mut receiver);
+        // a single message larger than the limit is still queued.
+        assert!(matches!(
+            sender.push(message("12345")),
+            Push::Delivered {
+                over_limit: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn bounded() {
+        for policy in [
+            SlowConsumerPolicy::DropNewest,
+            SlowConsumerPolicy::DropOldest,
+        ] {
+            let (sender, receiver) = channel(limits(policy));
+            for _ in 0..100 {
+                sender.push(message("1"));
+                assert!(sender.shared.state.lock().unwrap().messages.len() <= 2);
+            }
+            assert_eq!(receiver.dropped(), 98);
+
+            receiver.set_limits(100, 4);
+            for _ in 0..100 {
+                sender.push(message("12"));
+                assert!(sender.shared.state.lock().unwrap().bytes <= 4);
+            }
+        }
+    }
+
+    #[test]
+    fn unbounded() {
+        let (sender, mut receiver) = channel(limits(SlowConsumerPolicy::Unbounded));
+        for payload in ["1", "2", "3"] {
+            sender.push(message(payload));
+        }
+
+        assert_eq!(receiver.dropped(), 0);
+        for payload in ["1", "2", "3"] {
+            assert_eq!(recv(&mut receiver).unwrap().payload, payload);
+        }
+    }
+
+    #[test]
+    fn closed() {
+        let (sender, mut receiver) = channel(limits(SlowConsumerPolicy::DropNewest));
+        sender.push(message("1"));
+        receiver.close();
+
+        assert!(sender.is_closed());
+        assert!(matches!(sender.push(message("2")), Push::Closed));
+        drop(sender);
+        assert_eq!(recv(&mut receiver).unwrap().payload, "1");
+        assert!(recv(&mut receiver).is_none());
+    }
+}
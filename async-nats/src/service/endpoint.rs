@@ -0,0 +1,181 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{Error, State, NATS_SERVICE_ERROR, NATS_SERVICE_ERROR_CODE};
+use crate::header::HeaderMap;
+use crate::message::RespondError;
+use crate::{Client, Message, Subscriber};
+use bytes::Bytes;
+use futures::{Future, Stream, StreamExt};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// A stream of [Requests][Request] sent to an endpoint of a [Service][super::Service].
+/// Ends when the service is stopped or dropped.
+pub struct Endpoint {
+    name: String,
+    subscriber: Subscriber,
+    client: Client,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl Endpoint {
+    pub(super) fn new(
+        name: String,
+        subscriber: Subscriber,
+        client: Client,
+        state: Arc<Mutex<State>>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Endpoint {
+        Endpoint {
+            name,
+            subscriber,
+            client,
+            state,
+            shutdown: Some(Box::pin(async move {
+                // the sender is dropped along with the service.
+                while !*shutdown.borrow() {
+                    if shutdown.changed().await.is_err() {
+                        break;
+                    }
+                }
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Endpoint")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Stream for Endpoint {
+    type Item = Request;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.shutdown.as_mut() {
+            Some(shutdown) => {
+                if shutdown.as_mut().poll(cx).is_ready() {
+                    self.shutdown = None;
+                    return Poll::Ready(None);
+                }
+            }
+            None => return Poll::Ready(None),
+        }
+
+        match self.subscriber.poll_next_unpin(cx) {
+            Poll::Ready(Some(message)) => {
+                if let Some(endpoint) = self.state.lock().unwrap().endpoints.get_mut(&self.name) {
+                    endpoint.stats.num_requests += 1;
+                }
+                Poll::Ready(Some(Request {
+                    message,
+                    issued: Instant::now(),
+                    endpoint: self.name.clone(),
+                    client: self.client.clone(),
+                    state: self.state.clone(),
+                }))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A request received by an [Endpoint].
+#[derive(Debug)]
+pub struct Request {
+    /// The request message.
+    pub message: Message,
+    issued: Instant,
+    endpoint: String,
+    client: Client,
+    state: Arc<Mutex<State>>,
+}
+
+impl Request {
+    /// Responds to the request with the payload, or with an empty payload and the error
+    /// set in the [NATS_SERVICE_ERROR] and [NATS_SERVICE_ERROR_CODE] headers, where line
+    /// breaks in the description are replaced by spaces.
+    /// The time since the request was received is added to the endpoint processing time.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    ///
+    /// # let client = async_nats::connect("localhost:4222").await?;
+    /// # let service = async_nats::service::add(client, async_nats::service::Config {
+    /// #     name: "generator".to_string(),
+    /// #     version: "1.0.0".to_string(),
+    /// #     ..Default::default()
+    /// # }).await?;
+    /// let mut endpoint = service.endpoint("generator.length").await?;
+    /// while let Some(request) = endpoint.next().await {
+    ///     let response = match std::str::from_utf8(&request.message.payload) {
+    ///         Ok(text) => Ok(text.len().to_string().into()),
+    ///         Err(err) => Err(async_nats::service::Error {
+    ///             code: 400,
+    ///             description: err.to_string(),
+    ///         }),
+    ///     };
+    ///     request.respond(response).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn respond(&self, response: Result<Bytes, Error>) -> Result<(), RespondError> {
+        let reply = self
+            .message
+            .reply
+            .clone()
+            .ok_or(RespondError::NoReplySubject)?;
+
+        let result = match &response {
+            Ok(payload) => self.client.publish(reply, payload.clone()).await,
+            Err(err) => {
+                let mut headers = HeaderMap::new();
+                let description = err.description.replace(['\r', '\n'], " ");
+                headers.insert(NATS_SERVICE_ERROR, description.parse().unwrap_or_default());
+                headers.insert(NATS_SERVICE_ERROR_CODE, err.code.into());
+                self.client
+                    .publish_with_headers(reply, headers, Bytes::new())
+                    .await
+            }
+        };
+
+        let elapsed = self.issued.elapsed();
+        if let Some(endpoint) = self.state.lock().unwrap().endpoints.get_mut(&self.endpoint) {
+            endpoint.responses += 1;
+            endpoint.stats.processing_time += elapsed;
+            endpoint.stats.average_processing_time =
+                endpoint.stats.processing_time / endpoint.responses;
+            if let Err(err) = &response {
+                endpoint.stats.num_errors += 1;
+                endpoint.stats.last_error = Some(err.to_string());
+            }
+        }
+
+        result.map_err(RespondError::Publish)
+    }
+}
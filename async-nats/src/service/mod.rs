@@ -0,0 +1,637 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! NATS services: request/reply endpoints which can be discovered and monitored through
+//! the `$SRV` subjects.
+//!
+//! A [Service] answers `$SRV.PING`, `$SRV.INFO`, `$SRV.STATS` and `$SRV.SCHEMA` requests,
+//! either for all services, for all instances of a service by name (`$SRV.PING.<name>`),
+//! or for a single instance (`$SRV.PING.<name>.<id>`).
+//! Its [Endpoints][Endpoint] subscribe in the [QUEUE_GROUP] queue group, so requests are
+//! balanced between instances of the same service.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), async_nats::Error> {
+//! use futures::StreamExt;
+//!
+//! let client = async_nats::connect("localhost:4222").await?;
+//! let service = async_nats::service::add(
+//!     client,
+//!     async_nats::service::Config {
+//!         name: "generator".to_string(),
+//!         version: "1.0.0".to_string(),
+//!         ..Default::default()
+//!     },
+//! )
+//! .await?;
+//!
+//! let mut endpoint = service.endpoint("generator.echo").await?;
+//! while let Some(request) = endpoint.next().await {
+//!     let payload = request.message.payload.clone();
+//!     request.respond(Ok(payload)).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod endpoint;
+
+pub use endpoint::{Endpoint, Request};
+
+use crate::{Client, Message, SubscribeError};
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Prefix of the discovery subjects.
+pub const SERVICE_API_PREFIX: &str = "$SRV";
+/// Queue group in which all endpoints subscribe.
+pub const QUEUE_GROUP: &str = "q";
+/// Header carrying the description of an [Error] returned by an endpoint.
+pub const NATS_SERVICE_ERROR: &str = "Nats-Service-Error";
+/// Header carrying the code of an [Error] returned by an endpoint.
+pub const NATS_SERVICE_ERROR_CODE: &str = "Nats-Service-Error-Code";
+
+const PING_RESPONSE_TYPE: &str = "io.nats.micro.v1.ping_response";
+const INFO_RESPONSE_TYPE: &str = "io.nats.micro.v1.info_response";
+const STATS_RESPONSE_TYPE: &str = "io.nats.micro.v1.stats_response";
+const SCHEMA_RESPONSE_TYPE: &str = "io.nats.micro.v1.schema_response";
+
+lazy_static! {
+    static ref NAME_RE: Regex = Regex::new(r#"\A[A-Za-z0-9\-_]+\z"#).unwrap();
+    static ref SEMVER_RE: Regex = Regex::new(
+        r#"\A(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(-[0-9A-Za-z\-\.]+)?(\+[0-9A-Za-z\-\.]+)?\z"#
+    )
+    .unwrap();
+}
+
+/// Configuration of a [Service].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Config {
+    /// Name of the service, shared by all of its instances.
+    /// Can contain only alphanumerics, `-` and `_`.
+    pub name: String,
+    /// Optional human readable description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Version of the service, in the [semver](https://semver.org) format.
+    pub version: String,
+    /// Optional schema of the requests and responses, returned by `$SRV.SCHEMA`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+}
+
+/// Schema of the requests and responses of a [Service].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Schema {
+    pub request: String,
+    pub response: String,
+}
+
+/// Response to a `$SRV.PING` request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PingResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub id: String,
+    pub version: String,
+}
+
+/// Response to a `$SRV.INFO` request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Info {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub version: String,
+    /// Subjects of the service endpoints.
+    pub subjects: Vec<String>,
+}
+
+/// Response to a `$SRV.STATS` request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Stats {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub id: String,
+    pub version: String,
+    /// When the service was started, or its stats were last reset.
+    #[serde(with = "rfc3339")]
+    pub started: OffsetDateTime,
+    pub endpoints: Vec<EndpointStats>,
+}
+
+/// Statistics of a single [Endpoint].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EndpointStats {
+    pub name: String,
+    pub subject: String,
+    /// Number of requests received.
+    pub num_requests: usize,
+    /// Number of requests responded to with an [Error].
+    pub num_errors: usize,
+    /// The last [Error] returned, formatted as `code:description`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Total time spent between receiving requests and responding to them.
+    #[serde(with = "serde_nanos")]
+    pub processing_time: Duration,
+    #[serde(with = "serde_nanos")]
+    pub average_processing_time: Duration,
+}
+
+/// Response to a `$SRV.SCHEMA` request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub id: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+}
+
+/// An error returned by an endpoint, sent to the requester in the [NATS_SERVICE_ERROR]
+/// and [NATS_SERVICE_ERROR_CODE] headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub code: usize,
+    pub description: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.code, self.description)
+    }
+}
+
+impl error::Error for Error {}
+
+/// An error returned from the [add] function.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AddError {
+    /// The service name is empty or contains characters other than `A-Z`, `a-z`, `0-9`,
+    /// `-` and `_`.
+    InvalidName,
+    /// The service version is not a valid semantic version.
+    InvalidVersion,
+    /// Subscribing to the discovery subjects failed.
+    Subscribe(SubscribeError),
+}
+
+impl fmt::Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddError::InvalidName => write!(f, "nats: invalid service name"),
+            AddError::InvalidVersion => write!(f, "nats: service version is not a valid semver"),
+            AddError::Subscribe(err) => write!(f, "nats: failed to subscribe: {}", err),
+        }
+    }
+}
+
+impl error::Error for AddError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AddError::Subscribe(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<SubscribeError> for AddError {
+    fn from(err: SubscribeError) -> Self {
+        AddError::Subscribe(err)
+    }
+}
+
+/// An error returned from the [Service::endpoint] method.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EndpointError {
+    /// An endpoint with the same subject was already added to the service.
+    AlreadyExists,
+    /// Subscribing to the endpoint subject failed.
+    Subscribe(SubscribeError),
+}
+
+impl fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndpointError::AlreadyExists => write!(f, "nats: endpoint was already added"),
+            EndpointError::Subscribe(err) => write!(f, "nats: failed to subscribe: {}", err),
+        }
+    }
+}
+
+impl error::Error for EndpointError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            EndpointError::Subscribe(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The kinds of discovery requests answered by a [Service].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verb {
+    Ping,
+    Info,
+    Stats,
+    Schema,
+}
+
+impl fmt::Display for Verb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verb::Ping => write!(f, "PING"),
+            Verb::Info => write!(f, "INFO"),
+            Verb::Stats => write!(f, "STATS"),
+            Verb::Schema => write!(f, "SCHEMA"),
+        }
+    }
+}
+
+/// Returns the discovery subject for the verb, narrowed to a service name and instance id
+/// if they are given.
+///
+/// # Examples
+/// ```
+/// use async_nats::service::{verb_subject, Verb};
+///
+/// assert_eq!(verb_subject(Verb::Ping, None, None), "$SRV.PING");
+/// assert_eq!(
+///     verb_subject(Verb::Stats, Some("generator"), None),
+///     "$SRV.STATS.generator"
+/// );
+/// ```
+pub fn verb_subject(verb: Verb, name: Option<&str>, id: Option<&str>) -> String {
+    match (name, id) {
+        (Some(name), Some(id)) => format!("{}.{}.{}.{}", SERVICE_API_PREFIX, verb, name, id),
+        (Some(name), None) => format!("{}.{}.{}", SERVICE_API_PREFIX, verb, name),
+        _ => format!("{}.{}", SERVICE_API_PREFIX, verb),
+    }
+}
+
+/// State shared between the [Service], its endpoints and the discovery task.
+#[derive(Debug)]
+struct State {
+    id: String,
+    config: Config,
+    started: OffsetDateTime,
+    endpoints: BTreeMap<String, EndpointState>,
+}
+
+/// Statistics of an endpoint, along with the number of requests responded to, which is
+/// used for the average processing time.
+#[derive(Debug, Default)]
+struct EndpointState {
+    stats: EndpointStats,
+    responses: u32,
+}
+
+impl State {
+    fn ping(&self) -> PingResponse {
+        PingResponse {
+            kind: PING_RESPONSE_TYPE.to_string(),
+            name: self.config.name.clone(),
+            id: self.id.clone(),
+            version: self.config.version.clone(),
+        }
+    }
+
+    fn info(&self) -> Info {
+        Info {
+            kind: INFO_RESPONSE_TYPE.to_string(),
+            name: self.config.name.clone(),
+            id: self.id.clone(),
+            description: self.config.description.clone(),
+            version: self.config.version.clone(),
+            subjects: self
+                .endpoints
+                .values()
+                .map(|endpoint| endpoint.stats.subject.clone())
+                .collect(),
+        }
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            kind: STATS_RESPONSE_TYPE.to_string(),
+            name: self.config.name.clone(),
+            id: self.id.clone(),
+            version: self.config.version.clone(),
+            started: self.started,
+            endpoints: self
+                .endpoints
+                .values()
+                .map(|endpoint| endpoint.stats.clone())
+                .collect(),
+        }
+    }
+
+    fn schema(&self) -> SchemaResponse {
+        SchemaResponse {
+            kind: SCHEMA_RESPONSE_TYPE.to_string(),
+            name: self.config.name.clone(),
+            id: self.id.clone(),
+            version: self.config.version.clone(),
+            schema: self.config.schema.clone(),
+        }
+    }
+
+    fn response(&self, verb: Verb) -> serde_json::Result<Vec<u8>> {
+        match verb {
+            Verb::Ping => serde_json::to_vec(&self.ping()),
+            Verb::Info => serde_json::to_vec(&self.info()),
+            Verb::Stats => serde_json::to_vec(&self.stats()),
+            Verb::Schema => serde_json::to_vec(&self.schema()),
+        }
+    }
+}
+
+/// Adds a new service instance and starts answering the discovery requests.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), async_nats::Error> {
+/// let client = async_nats::connect("localhost:4222").await?;
+/// let service = async_nats::service::add(
+///     client,
+///     async_nats::service::Config {
+///         name: "generator".to_string(),
+///         version: "1.0.0".to_string(),
+///         description: Some("generates things".to_string()),
+///         ..Default::default()
+///     },
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn add(client: Client, config: Config) -> Result<Service, AddError> {
+    Service::add(client, config).await
+}
+
+/// A running service instance, created with [add].
+///
+/// Dropping the service stops answering discovery requests and ends all of its endpoints.
+#[derive(Debug)]
+pub struct Service {
+    state: Arc<Mutex<State>>,
+    client: Client,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl Service {
+    async fn add(client: Client, config: Config) -> Result<Service, AddError> {
+        if !NAME_RE.is_match(&config.name) {
+            return Err(AddError::InvalidName);
+        }
+        if !SEMVER_RE.is_match(&config.version) {
+            return Err(AddError::InvalidVersion);
+        }
+
+        let id = nuid::next();
+        let mut subscribers = Vec::new();
+        for verb in [Verb::Ping, Verb::Info, Verb::Stats, Verb::Schema] {
+            for (name, id) in [
+                (None, None),
+                (Some(config.name.as_str()), None),
+                (Some(config.name.as_str()), Some(id.as_str())),
+            ] {
+                let subscriber = client.subscribe(verb_subject(verb, name, id)).await?;
+                subscribers.push(subscriber.map(move |message| (verb, message)));
+            }
+        }
+
+        let state = Arc::new(Mutex::new(State {
+            id,
+            config,
+            started: OffsetDateTime::now_utc(),
+            endpoints: BTreeMap::new(),
+        }));
+        let (shutdown, _) = watch::channel(false);
+
+        let task = tokio::spawn({
+            let client = client.clone();
+            let state = state.clone();
+            let mut requests = futures::stream::select_all(subscribers);
+            async move {
+                while let Some((verb, message)) = requests.next().await {
+                    let reply = match message.reply {
+                        Some(reply) => reply,
+                        None => continue,
+                    };
+                    let response = state.lock().unwrap().response(verb);
+                    match response {
+                        Ok(payload) => {
+                            if client.publish(reply, payload.into()).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(err) => {
+                            warn!("failed to serialize {} response: {}", verb, err);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Service {
+            state,
+            client,
+            shutdown,
+            task,
+        })
+    }
+
+    /// Adds an endpoint handling requests sent to the subject.
+    /// The subject is also the endpoint name in the [Stats], so it can be added only once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// use futures::StreamExt;
+    ///
+    /// # let client = async_nats::connect("localhost:4222").await?;
+    /// # let service = async_nats::service::add(client, async_nats::service::Config {
+    /// #     name: "generator".to_string(),
+    /// #     version: "1.0.0".to_string(),
+    /// #     ..Default::default()
+    /// # }).await?;
+    /// let mut endpoint = service.endpoint("generator.sum").await?;
+    /// while let Some(request) = endpoint.next().await {
+    ///     request
+    ///         .respond(Err(async_nats::service::Error {
+    ///             code: 501,
+    ///             description: "not implemented".to_string(),
+    ///         }))
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn endpoint<S: ToString>(&self, subject: S) -> Result<Endpoint, EndpointError> {
+        let subject = subject.to_string();
+        match self.state.lock().unwrap().endpoints.entry(subject.clone()) {
+            Entry::Occupied(_) => return Err(EndpointError::AlreadyExists),
+            Entry::Vacant(entry) => {
+                entry.insert(EndpointState {
+                    stats: EndpointStats {
+                        name: subject.clone(),
+                        subject: subject.clone(),
+                        ..Default::default()
+                    },
+                    responses: 0,
+                });
+            }
+        }
+
+        let subscriber = match self
+            .client
+            .queue_subscribe(subject.clone(), QUEUE_GROUP.to_string())
+            .await
+        {
+            Ok(subscriber) => subscriber,
+            Err(err) => {
+                self.state.lock().unwrap().endpoints.remove(&subject);
+                return Err(EndpointError::Subscribe(err));
+            }
+        };
+
+        Ok(Endpoint::new(
+            subject,
+            subscriber,
+            self.client.clone(),
+            self.state.clone(),
+            self.shutdown.subscribe(),
+        ))
+    }
+
+    /// Returns the id of this service instance.
+    pub fn id(&self) -> String {
+        self.state.lock().unwrap().id.clone()
+    }
+
+    /// Returns the same [Info] as a `$SRV.INFO` request.
+    pub fn info(&self) -> Info {
+        self.state.lock().unwrap().info()
+    }
+
+    /// Returns the same [Stats] as a `$SRV.STATS` request.
+    pub fn stats(&self) -> Stats {
+        self.state.lock().unwrap().stats()
+    }
+
+    /// Resets the statistics of all endpoints.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.started = OffsetDateTime::now_utc();
+        for endpoint in state.endpoints.values_mut() {
+            *endpoint = EndpointState {
+                stats: EndpointStats {
+                    name: endpoint.stats.name.clone(),
+                    subject: endpoint.stats.subject.clone(),
+                    ..Default::default()
+                },
+                responses: 0,
+            };
+        }
+    }
+
+    /// Stops answering discovery requests and ends all endpoints of the service.
+    /// Requests already received can still be responded to.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// # let client = async_nats::connect("localhost:4222").await?;
+    /// let service = async_nats::service::add(client, async_nats::service::Config {
+    ///     name: "generator".to_string(),
+    ///     version: "1.0.0".to_string(),
+    ///     ..Default::default()
+    /// }).await?;
+    /// service.stop().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stop(self) -> Result<(), crate::Error> {
+        self.shutdown.send_replace(true);
+        self.task.abort();
+        self.client.flush().await?;
+        Ok(())
+    }
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl Message {
+    /// Returns the [Error] a service endpoint responded with, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), async_nats::Error> {
+    /// let client = async_nats::connect("localhost:4222").await?;
+    /// let response = client.request("generator.sum".into(), "1,2".into()).await?;
+    /// if let Some(err) = response.service_error() {
+    ///     println!("service failed: {}", err);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn service_error(&self) -> Option<Error> {
+        let headers = self.headers.as_ref()?;
+        let description = headers.get(NATS_SERVICE_ERROR)?.to_string();
+        let code = headers
+            .get(NATS_SERVICE_ERROR_CODE)
+            .and_then(|code| code.as_str().parse().ok())
+            .unwrap_or_default();
+
+        Some(Error { code, description })
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// A snapshot of the connection counters, returned by
+/// [Client::statistics][crate::Client::statistics].
+///
+/// Counters are kept for the lifetime of the client and are not reset on reconnect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Statistics {
+    /// Number of messages received, including responses to requests.
+    pub in_messages: u64,
+    /// Number of messages published.
+    pub out_messages: u64,
+    /// Payload bytes received.
+    pub in_bytes: u64,
+    /// Payload bytes published.
+    pub out_bytes: u64,
+    /// Number of successful reconnects.
+    pub reconnects: u64,
+    /// Payload bytes not yet flushed to the server, including the ones buffered while
+    /// reconnecting.
+    pub pending_bytes: u64,
+    /// Number of messages dropped because the subscriber was too slow, by subscription id.
+    /// Only subscriptions which dropped messages are listed.
+    pub slow_consumers: HashMap<u64, u64>,
+}
+
+/// Counters shared between the [Client][crate::Client], the connection handler and
+/// the connection.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    pub(crate) in_messages: AtomicU64,
+    pub(crate) out_messages: AtomicU64,
+    pub(crate) in_bytes: AtomicU64,
+    pub(crate) out_bytes: AtomicU64,
+    pub(crate) reconnects: AtomicU64,
+    pub(crate) pending_bytes: AtomicU64,
+    pub(crate) slow_consumers: Mutex<HashMap<u64, u64>>,
+}
+
+impl Counters {
+    pub(crate) fn add_slow_consumers(&self, sid: u64, dropped: usize) {
+        *self.slow_consumers.lock().unwrap().entry(sid).or_insert(0) += dropped as u64;
+    }
+
+    pub(crate) fn snapshot(&self) -> Statistics {
+        Statistics {
+            in_messages: self.in_messages.load(Ordering::Relaxed),
+            out_messages: self.out_messages.load(Ordering::Relaxed),
+            in_bytes: self.in_bytes.load(Ordering::Relaxed),
+            out_bytes: self.out_bytes.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            pending_bytes: self.pending_bytes.load(Ordering::Relaxed),
+            slow_consumers: self.slow_consumers.lock().unwrap().clone(),
+        }
+    }
+}
@@ -12,7 +12,7 @@
 // limitations under the License.
 
 mod client {
-    use async_nats::{ConnectOptions, Event};
+    use async_nats::{ConnectOptions, ConnectionState, Event};
     use bytes::Bytes;
     use futures::future::join_all;
     use futures::stream::StreamExt;
@@ -128,7 +128,7 @@ mod client {
         let mut subscriber = client.subscribe("test".into()).await.unwrap();
 
         let mut headers = async_nats::HeaderMap::new();
-        headers.append("X-Test", b"Test".as_ref().try_into().unwrap());
+        headers.append("X-Test", "Test".parse().unwrap());
 
         client
             .publish_with_headers("test".into(), headers.clone(), b"".as_ref().into())
@@ -141,6 +141,64 @@ mod client {
         assert_eq!(message.headers.unwrap(), headers);
     }
 
+    #[tokio::test]
+    async fn header_case_and_values() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut subscriber = client.subscribe("test".into()).await.unwrap();
+
+        let mut headers = async_nats::HeaderMap::new();
+        headers.append("X-Mixed-Case", "one".parse().unwrap());
+        headers.append("X-Mixed-Case", "two".parse().unwrap());
+        headers.set_msg_id("id");
+        client
+            .publish_with_headers("test".into(), headers, "".into())
+            .await
+            .unwrap();
+
+        let headers = subscriber.next().await.unwrap().headers.unwrap();
+        let name = headers.keys().find(|name| *name == "x-mixed-case").unwrap();
+        assert_eq!(name.as_str(), "X-Mixed-Case");
+        assert_eq!(
+            headers.get_all("x-mixed-case").collect::<Vec<_>>(),
+            vec!["one", "two"]
+        );
+        assert_eq!(headers.msg_id(), Some("id"));
+    }
+
+    #[tokio::test]
+    async fn message_respond() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut subscriber = client.subscribe("service".into()).await.unwrap();
+        tokio::spawn({
+            let client = client.clone();
+            async move {
+                let message = subscriber.next().await.unwrap();
+                message.respond(&client, "pong".into()).await.unwrap();
+            }
+        });
+
+        let response = client
+            .request("service".into(), "ping".into())
+            .await
+            .unwrap();
+        assert_eq!(response.payload, "pong");
+
+        let mut subscriber = client.subscribe("events".into()).await.unwrap();
+        client
+            .publish("events".into(), "data".into())
+            .await
+            .unwrap();
+        let message = subscriber.next().await.unwrap();
+        assert!(matches!(
+            message.respond(&client, "".into()).await,
+            Err(async_nats::message::RespondError::NoReplySubject)
+        ));
+    }
+
     #[tokio::test]
     async fn publish_request() {
         let server = nats_server::run_basic_server();
@@ -198,13 +256,159 @@ mod client {
         let server = nats_server::run_basic_server();
         let client = async_nats::connect(server.client_url()).await.unwrap();
 
-        tokio::time::timeout(
+        let err = tokio::time::timeout(
             tokio::time::Duration::from_millis(300),
             client.request("test".into(), "request".into()),
         )
         .await
         .unwrap()
         .unwrap_err();
+        assert!(matches!(err, async_nats::RequestError::NoResponders));
+    }
+
+    #[tokio::test]
+    async fn request_timeout() {
+        let server = nats_server::run_basic_server();
+        let client = ConnectOptions::new()
+            .request_timeout(Some(Duration::from_millis(300)))
+            .connect(server.client_url())
+            .await
+            .unwrap();
+
+        // a subscriber that never responds.
+        let _sub = client.subscribe("test".into()).await.unwrap();
+
+        let err = client
+            .request("test".into(), "request".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, async_nats::RequestError::TimedOut));
+
+        // per request timeout overrides the default one.
+        let request = async_nats::Request::new()
+            .payload("request".into())
+            .timeout(Some(Duration::from_millis(100)));
+        tokio::time::timeout(
+            Duration::from_millis(200),
+            client.send_request("test".into(), request),
+        )
+        .await
+        .unwrap()
+        .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn request_builder() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut sub = client.subscribe("test".into()).await.unwrap();
+
+        tokio::spawn({
+            let client = client.clone();
+            async move {
+                let msg = sub.next().await.unwrap();
+                assert_eq!(msg.reply.as_deref(), Some("custom.inbox"));
+                assert_eq!(
+                    msg.headers.unwrap().get("key").unwrap(),
+                    &"value".parse::<async_nats::HeaderValue>().unwrap()
+                );
+                client
+                    .publish(msg.reply.unwrap(), "reply".into())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("key", "value".parse().unwrap());
+        let request = async_nats::Request::new()
+            .payload("request".into())
+            .headers(headers)
+            .inbox("custom.inbox".into());
+
+        let resp = client.send_request("test".into(), request).await.unwrap();
+        assert_eq!(resp.payload, Bytes::from("reply"));
+    }
+
+    #[tokio::test]
+    async fn request_multi() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        for i in 0..3 {
+            let mut sub = client.subscribe("test".into()).await.unwrap();
+            tokio::spawn({
+                let client = client.clone();
+                async move {
+                    let msg = sub.next().await.unwrap();
+                    client
+                        .publish(msg.reply.unwrap(), format!("reply {}", i).into())
+                        .await
+                        .unwrap();
+                }
+            });
+        }
+        client.flush().await.unwrap();
+
+        let responses = client
+            .request_multi("test".into(), "request".into())
+            .await
+            .unwrap();
+        let responses: Vec<async_nats::Message> = responses.take(3).collect().await;
+        assert_eq!(responses.len(), 3);
+
+        // the stream ends if there is nobody to respond.
+        let responses = client
+            .request_multi("nobody".into(), "request".into())
+            .await
+            .unwrap();
+        let responses: Vec<async_nats::Message> =
+            tokio::time::timeout(Duration::from_millis(500), responses.collect())
+                .await
+                .unwrap();
+        assert!(responses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn request_shared_inbox() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut sub = client.subscribe("test".into()).await.unwrap();
+
+        tokio::spawn({
+            let client = client.clone();
+            async move {
+                while let Some(msg) = sub.next().await {
+                    client
+                        .publish(msg.reply.unwrap(), msg.payload)
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+
+        let responses = join_all((0..100).map(|i| {
+            let client = client.clone();
+            async move {
+                client
+                    .request("test".into(), i.to_string().into())
+                    .await
+                    .unwrap()
+            }
+        }))
+        .await;
+
+        // every response is routed to its own request over the same inbox.
+        for (i, response) in responses.iter().enumerate() {
+            assert_eq!(response.payload, Bytes::from(i.to_string()));
+        }
+        let (prefix, _) = responses[0].subject.rsplit_once('.').unwrap();
+        assert!(prefix.starts_with("_INBOX."));
+        assert!(responses
+            .iter()
+            .all(|response| response.subject.starts_with(prefix)));
     }
 
     #[tokio::test]
@@ -246,6 +450,191 @@ mod client {
         }
         assert!(sub.next().await.is_none());
     }
+    #[tokio::test]
+    async fn subscriber_drain() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut sub = client.subscribe("test".into()).await.unwrap();
+
+        for _ in 0..10 {
+            client.publish("test".into(), "data".into()).await.unwrap();
+        }
+        sub.drain().await.unwrap();
+        client.publish("test".into(), "data".into()).await.unwrap();
+        client.flush().await.unwrap();
+
+        let messages: Vec<async_nats::Message> = sub.collect().await;
+        assert_eq!(messages.len(), 10);
+
+        // the client is still usable after draining a subscription.
+        let mut sub = client.subscribe("test".into()).await.unwrap();
+        client.publish("test".into(), "data".into()).await.unwrap();
+        assert!(sub.next().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn subscribe_with_handler() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let handler = client
+            .subscribe_with_handler("test".into(), move |message| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(message.payload).unwrap();
+                }
+            })
+            .await
+            .unwrap();
+
+        for i in 0..10 {
+            client
+                .publish("test".into(), i.to_string().into())
+                .await
+                .unwrap();
+        }
+        for i in 0..10 {
+            assert_eq!(rx.recv().await.unwrap(), i.to_string());
+        }
+
+        handler.unsubscribe().await.unwrap();
+        client.publish("test".into(), "data".into()).await.unwrap();
+        client.flush().await.unwrap();
+        // the handler task was stopped, dropping the closure and its sender.
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn concurrent_handler_drain() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let handled = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handler = client
+            .subscribe("test".into())
+            .await
+            .unwrap()
+            .with_concurrent_handler(5, {
+                let handled = handled.clone();
+                move |_| {
+                    let handled = handled.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        handled.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            });
+
+        for _ in 0..20 {
+            client.publish("test".into(), "data".into()).await.unwrap();
+        }
+        client.flush().await.unwrap();
+
+        let start = std::time::Instant::now();
+        handler.drain().await.unwrap();
+        assert_eq!(handled.load(std::sync::atomic::Ordering::Relaxed), 20);
+        // handled five at a time instead of one by one.
+        assert!(start.elapsed() < Duration::from_millis(20 * 50));
+    }
+
+    #[tokio::test]
+    async fn typed_payloads() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut subscriber = client
+            .subscribe_typed::<Vec<u64>>("numbers".into())
+            .await
+            .unwrap();
+        client
+            .publish_json("numbers".into(), &vec![1, 2, 3])
+            .await
+            .unwrap();
+        client
+            .publish("numbers".into(), "not json".into())
+            .await
+            .unwrap();
+
+        let message = subscriber.next().await.unwrap();
+        assert_eq!(message.value.unwrap(), vec![1, 2, 3]);
+        assert_eq!(
+            message
+                .message
+                .headers
+                .unwrap()
+                .get("content-type")
+                .unwrap(),
+            "application/json"
+        );
+        assert!(matches!(
+            subscriber.next().await.unwrap().value,
+            Err(async_nats::codec::CodecError::Decode(_))
+        ));
+
+        let mut requests = client
+            .subscribe_typed::<Vec<u64>>("sum".into())
+            .await
+            .unwrap();
+        tokio::spawn({
+            let client = client.clone();
+            async move {
+                let request = requests.next().await.unwrap();
+                let sum: u64 = request.value.unwrap().iter().sum();
+                client
+                    .publish_json(request.message.reply.unwrap(), &sum)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let sum: u64 = client
+            .request_typed("sum".into(), &vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(sum, 6);
+
+        let err = client
+            .request_typed::<_, u64>("nobody".into(), &vec![1, 2, 3])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            async_nats::codec::TypedRequestError::Request(async_nats::RequestError::NoResponders)
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_drain() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut sub = client.subscribe("test".into()).await.unwrap();
+        let mut other = client.subscribe("other".into()).await.unwrap();
+
+        for _ in 0..10 {
+            client.publish("test".into(), "data".into()).await.unwrap();
+        }
+        client.drain().await.unwrap();
+
+        let mut count = 0;
+        while sub.next().await.is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 10);
+        assert!(other.next().await.is_none());
+
+        client
+            .publish("test".into(), "data".into())
+            .await
+            .unwrap_err();
+        client.drain().await.unwrap_err();
+        let _: async_nats::FlushError = client.flush().await.unwrap_err();
+        let _: async_nats::UnsubscribeError = sub.unsubscribe().await.unwrap_err();
+        let _: async_nats::DrainError = other.drain().await.unwrap_err();
+    }
+
     #[tokio::test]
     async fn unsubscribe_after_immediate() {
         let server = nats_server::run_basic_server();
@@ -432,6 +821,77 @@ mod client {
             .unwrap();
     }
 
+    #[tokio::test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    async fn lame_duck_migration() {
+        let cluster = nats_server::run_cluster("");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = ConnectOptions::new()
+            .event_callback(move |event| {
+                let tx = tx.clone();
+                async move {
+                    if let Event::Reconnect = event {
+                        tx.send(()).unwrap();
+                    }
+                }
+            })
+            .connect(cluster.client_url())
+            .await
+            .unwrap();
+
+        // wait for the INFO update advertising the other cluster members.
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while client.server_info().connect_urls.len() < 2 {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let server_id = client.server_info().server_id;
+
+        let mut sub = client.subscribe("data".into()).await.unwrap();
+        nats_server::set_lame_duck_mode(&cluster.servers[0]);
+        tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(client.server_info().server_id, server_id);
+
+        client.publish("data".into(), "data".into()).await.unwrap();
+        sub.next().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ignore_discovered_servers() {
+        let cluster = nats_server::run_cluster("");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = ConnectOptions::new()
+            .ignore_discovered_servers()
+            .max_reconnects(Some(2))
+            .event_callback(move |event| {
+                let tx = tx.clone();
+                async move {
+                    if let Event::Closed = event {
+                        tx.send(()).unwrap();
+                    }
+                }
+            })
+            .connect(cluster.client_url())
+            .await
+            .unwrap();
+        client.flush().await.unwrap();
+
+        // the other cluster members are not used once the only known server is gone.
+        let mut servers = cluster.servers;
+        drop(servers.remove(0));
+        tokio::time::timeout(Duration::from_secs(15), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn slow_consumers() {
         let server = nats_server::run_basic_server();
@@ -478,6 +938,159 @@ mod client {
             .unwrap()
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn slow_consumer_policies() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut newest = client.subscribe("data".into()).await.unwrap();
+        newest.set_pending_limits(1, 1024);
+        let mut oldest = client.subscribe("data".into()).await.unwrap();
+        oldest.set_pending_limits(1, 1024);
+        oldest.set_slow_consumer_policy(async_nats::SlowConsumerPolicy::DropOldest);
+        let mut unbounded = client.subscribe("data".into()).await.unwrap();
+        unbounded.set_pending_limits(1, 1024);
+        unbounded.set_slow_consumer_policy(async_nats::SlowConsumerPolicy::Unbounded);
+        client.flush().await.unwrap();
+
+        for i in 0..3 {
+            client
+                .publish("data".into(), i.to_string().into())
+                .await
+                .unwrap();
+        }
+        client.flush().await.unwrap();
+
+        for i in 0..3 {
+            let message = unbounded.next().await.unwrap();
+            assert_eq!(message.payload, i.to_string());
+        }
+        assert_eq!(unbounded.dropped_messages(), 0);
+
+        assert_eq!(newest.next().await.unwrap().payload, "0");
+        assert_eq!(newest.dropped_messages(), 2);
+        assert_eq!(oldest.next().await.unwrap().payload, "2");
+        assert_eq!(oldest.dropped_messages(), 2);
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_bounded() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let mut newest = client.subscribe("data".into()).await.unwrap();
+        newest.set_pending_limits(10, 1024);
+        let mut oldest = client.subscribe("data".into()).await.unwrap();
+        oldest.set_pending_limits(1000, 100);
+        oldest.set_slow_consumer_policy(async_nats::SlowConsumerPolicy::DropOldest);
+        client.flush().await.unwrap();
+
+        for _ in 0..1000 {
+            client
+                .publish("data".into(), "0123456789".into())
+                .await
+                .unwrap();
+        }
+        client.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // no more than the pending limits were kept for either subscriber.
+        assert_eq!(newest.dropped_messages(), 990);
+        assert_eq!(oldest.dropped_messages(), 990);
+        for subscriber in [&mut newest, &mut oldest] {
+            for _ in 0..10 {
+                subscriber.next().await.unwrap();
+            }
+            tokio::time::timeout(Duration::from_millis(100), subscriber.next())
+                .await
+                .unwrap_err();
+        }
+    }
+
+    #[tokio::test]
+    async fn statistics() {
+        let server = nats_server::run_basic_server();
+        let client = ConnectOptions::new()
+            .subscription_capacity(1)
+            .connect(server.client_url())
+            .await
+            .unwrap();
+
+        let mut subscriber = client.subscribe("data".into()).await.unwrap();
+        client.flush().await.unwrap();
+        for _ in 0..3 {
+            client.publish("data".into(), "data".into()).await.unwrap();
+        }
+        client.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let statistics = client.statistics();
+        assert_eq!(statistics.out_messages, 3);
+        assert_eq!(statistics.out_bytes, 12);
+        assert_eq!(statistics.in_messages, 3);
+        assert_eq!(statistics.in_bytes, 12);
+        assert_eq!(statistics.pending_bytes, 0);
+        assert_eq!(statistics.reconnects, 0);
+        assert_eq!(statistics.slow_consumers.values().sum::<u64>(), 2);
+
+        subscriber.next().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_drains_keep_connection() {
+        let server = nats_server::run_basic_server();
+        let client = ConnectOptions::new()
+            .ping_interval(Duration::from_millis(10))
+            .connect(server.client_url())
+            .await
+            .unwrap();
+
+        for _ in 0..10 {
+            let mut first = client.subscribe("test".into()).await.unwrap();
+            let mut second = client.subscribe("test".into()).await.unwrap();
+            first.drain().await.unwrap();
+            second.drain().await.unwrap();
+            assert!(first.next().await.is_none());
+            assert!(second.next().await.is_none());
+        }
+
+        // the drain pings did not count as unanswered keepalive pings.
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+        assert_eq!(client.statistics().reconnects, 0);
+    }
+
+    #[tokio::test]
+    async fn connection_state() {
+        let server = nats_server::run_basic_server();
+        let port = server.client_port().to_string();
+
+        let client = ConnectOptions::new()
+            .max_reconnects(None)
+            .connect(server.client_url())
+            .await
+            .unwrap();
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+
+        let mut state = client.connection_state_watcher();
+        drop(server);
+        tokio::time::timeout(Duration::from_secs(15), state.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(*state.borrow(), ConnectionState::Disconnected);
+
+        let _server = nats_server::run_server_with_port("", Some(port.as_str()));
+        tokio::time::timeout(Duration::from_secs(15), state.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+
+        client.drain().await.unwrap();
+        assert_eq!(client.connection_state(), ConnectionState::Closed);
+    }
+
     #[tokio::test]
     async fn no_echo() {
         // no_echo disabled.
@@ -533,6 +1146,138 @@ mod client {
         rx.recv().await;
     }
 
+    #[tokio::test]
+    async fn max_reconnects() {
+        let server = nats_server::run_basic_server();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = ConnectOptions::new()
+            .max_reconnects(Some(2))
+            .event_callback(move |event| {
+                let tx = tx.clone();
+                async move {
+                    if let Event::Closed = event {
+                        tx.send(()).unwrap();
+                    }
+                }
+            })
+            .connect(server.client_url())
+            .await
+            .unwrap();
+        client.flush().await.unwrap();
+
+        drop(server);
+        tokio::time::timeout(Duration::from_secs(15), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        client
+            .publish("test".into(), "data".into())
+            .await
+            .expect_err("client should be closed");
+    }
+
+    #[tokio::test]
+    async fn reconnect_delay_callback() {
+        let server = nats_server::run_basic_server();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = ConnectOptions::new()
+            .max_reconnects(Some(3))
+            .reconnect_delay_callback(move |attempts| {
+                tx.send(attempts).ok();
+                Duration::from_millis(10)
+            })
+            .connect(server.client_url())
+            .await
+            .unwrap();
+        client.flush().await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), 0);
+
+        drop(server);
+        for expected in 0..3 {
+            let attempts = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(attempts, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_on_failed_connect() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+            .to_string();
+
+        let client = ConnectOptions::new()
+            .retry_on_failed_connect()
+            .max_reconnects(None)
+            .connect(format!("nats://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        // the server version is unknown until the first connection is established.
+        assert!(!client.is_server_compatible(2, 0, 0));
+
+        let mut subscriber = client.subscribe("test".into()).await.unwrap();
+        client.publish("test".into(), "data".into()).await.unwrap();
+
+        let _server = nats_server::run_server_with_port("", Some(port.as_str()));
+
+        let message = tokio::time::timeout(Duration::from_secs(15), subscriber.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.payload, Bytes::from("data"));
+        assert!(client.is_server_compatible(2, 0, 0));
+        assert_eq!(client.statistics().reconnects, 0);
+    }
+
+    #[tokio::test]
+    async fn connect_failed_without_retry() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        async_nats::connect(format!("nats://127.0.0.1:{}", port))
+            .await
+            .expect_err("should fail to connect");
+    }
+
+    #[tokio::test]
+    async fn reconnect_buffer() {
+        let server = nats_server::run_basic_server();
+        let port = server.client_port().to_string();
+
+        let client = ConnectOptions::new()
+            .max_reconnects(None)
+            .connect(server.client_url())
+            .await
+            .unwrap();
+        let mut subscriber = client.subscribe("test".into()).await.unwrap();
+        client.flush().await.unwrap();
+
+        drop(server);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        for _ in 0..10 {
+            client.publish("test".into(), "data".into()).await.unwrap();
+        }
+
+        let _server = nats_server::run_server_with_port("", Some(port.as_str()));
+
+        for _ in 0..10 {
+            tokio::time::timeout(Duration::from_secs(15), subscriber.next())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+
     #[tokio::test]
     async fn inbox_prefix() {
         let server = nats_server::run_basic_server();
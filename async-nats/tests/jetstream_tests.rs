@@ -31,8 +31,10 @@ mod jetstream {
     use async_nats::jetstream::consumer::{
         self, DeliverPolicy, OrderedPushConsumer, PullConsumer, PushConsumer,
     };
-    use async_nats::jetstream::response::Response;
-    use async_nats::jetstream::stream::{self, DiscardPolicy, StorageType};
+    use async_nats::jetstream::publish::Publish;
+    use async_nats::jetstream::response::{ErrorCode, Response};
+    use async_nats::jetstream::stream::{self, DiscardPolicy, Purge, StorageType};
+    use async_nats::jetstream::RequestError;
     use async_nats::ConnectOptions;
     use bytes::Bytes;
     use futures::stream::{StreamExt, TryStreamExt};
@@ -78,6 +80,192 @@ mod jetstream {
         assert_eq!(ack.sequence, 1);
     }
 
+    #[tokio::test]
+    async fn publish_async() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client.clone()).max_pending_acks(10);
+
+        context
+            .create_stream(stream::Config {
+                name: "events".to_string(),
+                subjects: vec!["events".into()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut acks = Vec::new();
+        for i in 0..100 {
+            acks.push(
+                context
+                    .publish_async("events".into(), i.to_string().into())
+                    .await
+                    .unwrap(),
+            );
+            assert!(context.pending_acks() <= 10);
+        }
+        for (i, ack) in acks.into_iter().enumerate() {
+            assert_eq!(ack.await.unwrap().sequence, i as u64 + 1);
+        }
+
+        for i in 0..50 {
+            context
+                .publish_async("events".into(), i.to_string().into())
+                .await
+                .unwrap();
+        }
+        context.wait_for_pending_acks().await;
+        assert_eq!(context.pending_acks(), 0);
+
+        // a limit of 0 still lets one publish at a time through.
+        let unbuffered = async_nats::jetstream::new(client.clone()).max_pending_acks(0);
+        let ack = tokio::time::timeout(
+            Duration::from_secs(5),
+            unbuffered.publish_async("events".into(), "data".into()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        ack.await.unwrap();
+
+        let mut stream = context.get_stream("events").await.unwrap();
+        assert_eq!(stream.info().await.unwrap().state.messages, 151);
+
+        let err = context
+            .send_publish_async(
+                "events".into(),
+                Publish::new()
+                    .payload("data".into())
+                    .expected_last_sequence(1),
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestError::WrongLastSequence(_)));
+
+        let err = context
+            .publish_async("nothing".into(), "data".into())
+            .await
+            .unwrap()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RequestError::Request(async_nats::RequestError::NoResponders)
+        ));
+
+        // a subscriber which never responds lets the acknowledgement time out.
+        let _subscriber = client.subscribe("silent".into()).await.unwrap();
+        let err = context
+            .send_publish_async(
+                "silent".into(),
+                Publish::new()
+                    .payload("data".into())
+                    .timeout(Duration::from_millis(200)),
+            )
+            .await
+            .unwrap()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RequestError::Request(async_nats::RequestError::TimedOut)
+        ));
+    }
+
+    #[tokio::test]
+    async fn send_publish() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        context
+            .create_stream(stream::Config {
+                name: "accounts".to_string(),
+                subjects: vec!["accounts.>".into()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let ack = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new()
+                    .payload("opened".into())
+                    .message_id("1")
+                    .expected_stream("accounts")
+                    .expected_last_subject_sequence(0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ack.sequence, 1);
+        assert!(!ack.duplicate);
+
+        // the same message id is deduplicated.
+        let ack = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new().payload("opened".into()).message_id("1"),
+            )
+            .await
+            .unwrap();
+        assert!(ack.duplicate);
+
+        let err = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new()
+                    .payload("deposited".into())
+                    .expected_last_subject_sequence(0),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestError::WrongLastSequence(_)));
+        assert_eq!(
+            err.error_code(),
+            Some(ErrorCode::STREAM_WRONG_LAST_SEQUENCE)
+        );
+
+        let err = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new()
+                    .payload("deposited".into())
+                    .expected_last_message_id("2"),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RequestError::WrongLastMessageId(_)));
+
+        let err = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new()
+                    .payload("deposited".into())
+                    .expected_stream("other"),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::STREAM_NOT_MATCH));
+
+        let ack = context
+            .send_publish(
+                "accounts.1".into(),
+                Publish::new()
+                    .payload("deposited".into())
+                    .message_id("2")
+                    .expected_last_sequence(1)
+                    .expected_last_message_id("1")
+                    .expected_last_subject_sequence(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ack.sequence, 2);
+    }
+
     #[tokio::test]
     async fn request() {
         let server = nats_server::run_server("tests/configs/jetstream.conf");
@@ -130,6 +318,53 @@ mod jetstream {
         assert!(matches!(response, Response::Err { .. }));
     }
 
+    #[tokio::test]
+    async fn api_errors() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let err = context.get_stream("nonexisting").await.unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::STREAM_NOT_FOUND));
+
+        let err = context.get_stream("").await.unwrap_err();
+        assert!(matches!(err, RequestError::InvalidArgument(_)));
+
+        let err = context
+            .publish("nonexisting".into(), "data".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RequestError::Request(async_nats::RequestError::NoResponders)
+        ));
+
+        context.create_stream("events").await.unwrap();
+        context
+            .publish("events".into(), "data".into())
+            .await
+            .unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Nats-Expected-Last-Sequence", "10".parse().unwrap());
+        let err = context
+            .publish_with_headers("events".into(), headers, "data".into())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.error_code(),
+            Some(ErrorCode::STREAM_WRONG_LAST_SEQUENCE)
+        );
+
+        let stream = context.get_stream("events").await.unwrap();
+        let err = stream
+            .get_consumer::<consumer::pull::Config>("nonexisting")
+            .await
+            .err()
+            .expect("consumer should not exist");
+        assert_eq!(err.error_code(), Some(ErrorCode::CONSUMER_NOT_FOUND));
+    }
+
     // Interesting edge case with Jetstream
     #[tokio::test]
     #[ignore]
@@ -295,6 +530,83 @@ mod jetstream {
         assert_eq!(info.config.max_messages_per_subject, 100);
     }
 
+    #[tokio::test]
+    async fn list_streams() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        // more streams than fit in a single page of the stream list.
+        for i in 0..300 {
+            context
+                .create_stream(stream::Config {
+                    name: format!("stream{}", i),
+                    subjects: vec![format!("events.{}", i)],
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let names: Vec<String> = context.stream_names().try_collect().await.unwrap();
+        assert_eq!(names.len(), 300);
+
+        let streams: Vec<stream::Info> = context.streams().try_collect().await.unwrap();
+        assert_eq!(streams.len(), 300);
+
+        let names: Vec<String> = context
+            .stream_names()
+            .subject("events.7")
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["stream7".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn purge_stream() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let mut stream = context
+            .create_stream(stream::Config {
+                name: "events".to_string(),
+                subjects: vec!["events.>".into()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        for subject in ["events.a", "events.b"] {
+            for _ in 0..10 {
+                context
+                    .publish(subject.into(), "data".into())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let response = stream
+            .send_purge(Purge::new().filter("events.a").keep(3))
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert_eq!(response.purged, 7);
+
+        // purges the first five messages of events.b, at sequences 11 to 15.
+        let response = stream
+            .send_purge(Purge::new().filter("events.b").sequence(16))
+            .await
+            .unwrap();
+        assert_eq!(response.purged, 5);
+        assert_eq!(stream.info().await.unwrap().state.messages, 8);
+
+        let response = stream.purge().await.unwrap();
+        assert_eq!(response.purged, 8);
+        assert_eq!(stream.info().await.unwrap().state.messages, 0);
+    }
+
     #[tokio::test]
     async fn get_raw_message() {
         let server = nats_server::run_server("tests/configs/jetstream.conf");
@@ -348,6 +660,70 @@ mod jetstream {
         assert_eq!(raw_message.sequence, publish_ack.sequence);
     }
 
+    #[tokio::test]
+    async fn direct_get() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let stream = context
+            .create_stream(stream::Config {
+                name: "events".to_string(),
+                subjects: vec!["events.>".to_string()],
+                allow_direct: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Key", "value".parse().unwrap());
+        let first = context
+            .publish_with_headers("events.a".into(), headers, "first".into())
+            .await
+            .unwrap();
+        context
+            .publish("events.b".into(), "second".into())
+            .await
+            .unwrap();
+        let third = context
+            .publish("events.a".into(), "third".into())
+            .await
+            .unwrap();
+
+        let message = stream.direct_get(first.sequence).await.unwrap();
+        let stored = stream.get_raw_message(first.sequence).await.unwrap();
+        assert_eq!(message.subject, "events.a");
+        assert_eq!(message.sequence, first.sequence);
+        assert_eq!(message.payload, "first");
+        assert_eq!(message.headers.get("X-Key").unwrap(), "value");
+        assert_eq!(message.headers.len(), 1);
+        assert_eq!(message.time, stored.time);
+
+        let message = stream
+            .direct_get_next_for_subject("events.a", Some(first.sequence + 1))
+            .await
+            .unwrap();
+        assert_eq!(message.sequence, third.sequence);
+
+        let message = stream
+            .direct_get_next_for_subject("events.>", None)
+            .await
+            .unwrap();
+        assert_eq!(message.sequence, first.sequence);
+
+        let message = stream
+            .direct_get_last_for_subject("events.a")
+            .await
+            .unwrap();
+        assert_eq!(message.sequence, third.sequence);
+        assert!(message.headers.is_empty());
+        assert_eq!(message.payload, "third");
+
+        let err = stream.direct_get(100).await.unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::NO_MESSAGE_FOUND));
+    }
+
     #[tokio::test]
     async fn delete_message() {
         let server = nats_server::run_server("tests/configs/jetstream.conf");
@@ -470,6 +846,45 @@ mod jetstream {
             .is_err());
     }
 
+    #[tokio::test]
+    async fn list_consumers() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let stream = context.get_or_create_stream("events").await.unwrap();
+        for _ in 0..5 {
+            context
+                .publish("events".into(), "data".into())
+                .await
+                .unwrap();
+        }
+
+        // more consumers than fit in a single page of the consumer list.
+        for i in 0..300 {
+            stream
+                .create_consumer(consumer::pull::Config {
+                    durable_name: Some(format!("consumer{}", i)),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut names: Vec<String> = stream.consumer_names().try_collect().await.unwrap();
+        names.sort();
+        assert_eq!(names.len(), 300);
+        assert_eq!(names[0], "consumer0");
+
+        let consumers: Vec<consumer::Info> = stream.consumers().try_collect().await.unwrap();
+        assert_eq!(consumers.len(), 300);
+        for info in consumers {
+            assert_eq!(info.stream_name, "events");
+            assert_eq!(info.num_pending, 5);
+            assert_eq!(info.num_ack_pending, 0);
+        }
+    }
+
     #[tokio::test]
     async fn get_consumer() {
         let server = nats_server::run_server("tests/configs/jetstream.conf");
@@ -1060,7 +1475,8 @@ mod jetstream {
             .unwrap()
             .take(1);
         while let Some(result) = iter.next().await {
-            result.expect_err("should be status error");
+            let err = result.expect_err("should be status error");
+            assert!(matches!(err, consumer::StreamError::Status { .. }));
         }
     }
     #[tokio::test]
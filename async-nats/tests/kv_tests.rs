@@ -0,0 +1,320 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod kv {
+    use std::time::Duration;
+
+    use async_nats::jetstream::{
+        context::RequestError,
+        kv::{self, Operation},
+        stream::StorageType,
+    };
+    use bytes::Bytes;
+    use futures::{StreamExt, TryStreamExt};
+
+    #[tokio::test]
+    async fn create_bucket() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "test".to_string(),
+                description: "test_description".to_string(),
+                history: 10,
+                storage: StorageType::File,
+                num_replicas: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let status = kv.status().await.unwrap();
+        assert_eq!(status.bucket(), "test");
+        assert_eq!(status.history(), 10);
+        assert_eq!(status.values(), 0);
+
+        context
+            .create_key_value(kv::Config {
+                bucket: "invalid.name".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        context
+            .create_key_value(kv::Config {
+                bucket: "history".to_string(),
+                history: 65,
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        context.delete_key_value("test").await.unwrap();
+        context.get_key_value("test").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn get_key_value() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        context
+            .create_key_value(kv::Config {
+                bucket: "test".to_string(),
+                description: "test_description".to_string(),
+                history: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let kv = context.get_key_value("test").await.unwrap();
+        kv.put("key", "value".into()).await.unwrap();
+        assert_eq!(kv.bucket(), "test");
+        assert_eq!(kv.get("key").await.unwrap(), Some(Bytes::from("value")));
+
+        context.get_key_value("does_not_exist").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn put_get_delete() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "test".to_string(),
+                description: "test_description".to_string(),
+                history: 10,
+                storage: StorageType::File,
+                num_replicas: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let revision = kv.put("foo", "bar".into()).await.unwrap();
+        assert_eq!(revision, 1);
+
+        let entry = kv.entry("foo").await.unwrap().unwrap();
+        assert_eq!(entry.key, "foo");
+        assert_eq!(entry.value, Bytes::from("bar"));
+        assert_eq!(entry.revision, 1);
+        assert_eq!(entry.operation, Operation::Put);
+
+        assert_eq!(kv.get("foo").await.unwrap(), Some(Bytes::from("bar")));
+        assert_eq!(kv.get("nothing").await.unwrap(), None);
+        assert_eq!(kv.entry("nothing").await.unwrap(), None);
+
+        kv.delete("foo").await.unwrap();
+        assert_eq!(kv.get("foo").await.unwrap(), None);
+        let entry = kv.entry("foo").await.unwrap().unwrap();
+        assert_eq!(entry.operation, Operation::Delete);
+        assert_eq!(entry.revision, 2);
+
+        kv.put("foo", "baz".into()).await.unwrap();
+        assert_eq!(kv.get("foo").await.unwrap(), Some(Bytes::from("baz")));
+
+        kv.put("invalid key", "value".into()).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn create_and_update() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "test".to_string(),
+                history: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let revision = kv.create("key", "one".into()).await.unwrap();
+        // creating an already existing key fails.
+        assert!(matches!(
+            kv.create("key", "two".into()).await.unwrap_err(),
+            RequestError::WrongLastSequence(_)
+        ));
+        assert!(matches!(
+            kv.create("bad key", "two".into()).await.unwrap_err(),
+            RequestError::InvalidArgument(_)
+        ));
+
+        let revision = kv.update("key", "two".into(), revision).await.unwrap();
+        assert_eq!(kv.get("key").await.unwrap(), Some(Bytes::from("two")));
+
+        // updating with a stale revision fails.
+        kv.update("key", "three".into(), revision - 1)
+            .await
+            .unwrap_err();
+
+        // a deleted key can be created again.
+        kv.delete("key").await.unwrap();
+        kv.create("key", "four".into()).await.unwrap();
+        assert_eq!(kv.get("key").await.unwrap(), Some(Bytes::from("four")));
+    }
+
+    #[tokio::test]
+    async fn purge() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "test".to_string(),
+                history: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        kv.put("dz", "0".into()).await.unwrap();
+        kv.put("dz", "1".into()).await.unwrap();
+        kv.put("dz", "2".into()).await.unwrap();
+        kv.put("other", "value".into()).await.unwrap();
+
+        let history: Vec<kv::Entry> = kv.history("dz").await.unwrap().try_collect().await.unwrap();
+        assert_eq!(history.len(), 3);
+
+        kv.purge("dz").await.unwrap();
+        let history: Vec<kv::Entry> = kv.history("dz").await.unwrap().try_collect().await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].operation, Operation::Purge);
+        assert_eq!(kv.get("dz").await.unwrap(), None);
+        assert_eq!(kv.get("other").await.unwrap(), Some(Bytes::from("value")));
+    }
+
+    #[tokio::test]
+    async fn history() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "history".to_string(),
+                history: 15,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        for i in 0..20 {
+            kv.put("value", i.to_string().into()).await.unwrap();
+        }
+
+        let history: Vec<kv::Entry> = kv
+            .history("value")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 15);
+        assert_eq!(history.first().unwrap().value, Bytes::from("5"));
+        assert_eq!(history.last().unwrap().value, Bytes::from("19"));
+        assert_eq!(history.last().unwrap().delta, 0);
+    }
+
+    #[tokio::test]
+    async fn watch() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "watch".to_string(),
+                history: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        kv.put("foo", "initial".into()).await.unwrap();
+
+        let mut watch = kv.watch("foo").await.unwrap();
+        let mut watch_all = kv.watch_all().await.unwrap();
+
+        let entry = watch.next().await.unwrap().unwrap();
+        assert_eq!(entry.key, "foo");
+        assert_eq!(entry.value, Bytes::from("initial"));
+
+        tokio::task::spawn({
+            let kv = kv.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                kv.put("foo", "updated".into()).await.unwrap();
+                kv.put("bar", "other".into()).await.unwrap();
+                kv.delete("foo").await.unwrap();
+            }
+        });
+
+        let entry = watch.next().await.unwrap().unwrap();
+        assert_eq!(entry.value, Bytes::from("updated"));
+        assert_eq!(entry.operation, Operation::Put);
+        let entry = watch.next().await.unwrap().unwrap();
+        assert_eq!(entry.key, "foo");
+        assert_eq!(entry.operation, Operation::Delete);
+
+        let keys: Vec<String> = watch_all
+            .by_ref()
+            .take(4)
+            .map(|entry| entry.unwrap().key)
+            .collect()
+            .await;
+        assert_eq!(keys, vec!["foo", "foo", "bar", "foo"]);
+    }
+
+    #[tokio::test]
+    async fn keys() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let context = async_nats::jetstream::new(client);
+
+        let kv = context
+            .create_key_value(kv::Config {
+                bucket: "keys".to_string(),
+                history: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let keys: Vec<String> = kv.keys().await.unwrap().try_collect().await.unwrap();
+        assert!(keys.is_empty());
+
+        for i in 0..10 {
+            kv.put("bar", i.to_string().into()).await.unwrap();
+        }
+        for i in 0..10 {
+            kv.put("foo", i.to_string().into()).await.unwrap();
+        }
+        kv.put("deleted", "value".into()).await.unwrap();
+        kv.delete("deleted").await.unwrap();
+
+        let mut keys: Vec<String> = kv.keys().await.unwrap().try_collect().await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["bar", "foo"]);
+    }
+}
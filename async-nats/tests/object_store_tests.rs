@@ -0,0 +1,273 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod object_store {
+    use async_nats::jetstream::object_store::{Config, ObjectLink, ObjectMeta};
+    use futures::StreamExt;
+    use rand::RngCore;
+    use sha2::{Digest, Sha256};
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn get_and_put() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        let bucket = jetstream
+            .create_object_store(Config {
+                bucket: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let mut rng = rand::thread_rng();
+        let mut bytes = vec![0; 1024 * 1024 + 22];
+        rng.try_fill_bytes(&mut bytes).unwrap();
+
+        let info = bucket.put("FOO", &mut bytes.as_slice()).await.unwrap();
+        assert_eq!(info.name, "FOO");
+        assert_eq!(info.size, bytes.len());
+        assert_eq!(info.chunks, 9);
+        assert_eq!(
+            info.digest,
+            format!(
+                "SHA-256={}",
+                base64::encode_config(Sha256::digest(&bytes), base64::URL_SAFE)
+            )
+        );
+        assert_eq!(bucket.info("FOO").await.unwrap(), info);
+
+        let mut object = bucket.get("FOO").await.unwrap();
+        let mut result = Vec::new();
+        object.read_to_end(&mut result).await.unwrap();
+        assert_eq!(result, bytes);
+        assert_eq!(object.info(), &info);
+
+        // overwriting an object replaces its contents.
+        bucket.put("FOO", &mut "replaced".as_bytes()).await.unwrap();
+        let mut result = Vec::new();
+        bucket
+            .get("FOO")
+            .await
+            .unwrap()
+            .read_to_end(&mut result)
+            .await
+            .unwrap();
+        assert_eq!(result, b"replaced");
+
+        bucket.info("BAR").await.unwrap_err();
+        bucket.put("", &mut bytes.as_slice()).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn links() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        let source = jetstream
+            .create_object_store(Config {
+                bucket: "source".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let links = jetstream
+            .create_object_store(Config {
+                bucket: "links".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        source.put("FOO", &mut "data".as_bytes()).await.unwrap();
+        links
+            .put(
+                ObjectMeta {
+                    name: "LINK".to_string(),
+                    link: Some(ObjectLink {
+                        name: "FOO".to_string(),
+                        bucket: Some("source".to_string()),
+                    }),
+                    ..Default::default()
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        // the chunks are read from the linked bucket.
+        let mut result = Vec::new();
+        links
+            .get("LINK")
+            .await
+            .unwrap()
+            .read_to_end(&mut result)
+            .await
+            .unwrap();
+        assert_eq!(result, b"data");
+
+        links
+            .put(
+                ObjectMeta {
+                    name: "BUCKET".to_string(),
+                    link: Some(ObjectLink {
+                        name: "".to_string(),
+                        bucket: Some("source".to_string()),
+                    }),
+                    ..Default::default()
+                },
+                &mut "".as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert!(links.get("BUCKET").await.is_err());
+
+        // a link to a deleted object is not found, rather than an empty object.
+        source.delete("FOO").await.unwrap();
+        let err = links.get("LINK").await.err().unwrap();
+        assert_eq!(
+            err.downcast_ref::<std::io::Error>().unwrap().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn get_object_store() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        jetstream
+            .create_object_store(Config {
+                bucket: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let bucket = jetstream.get_object_store("bucket").await.unwrap();
+        bucket.put("FOO", &mut "data".as_bytes()).await.unwrap();
+
+        jetstream.get_object_store("unknown").await.unwrap_err();
+        jetstream
+            .get_object_store("invalid.name")
+            .await
+            .unwrap_err();
+
+        jetstream.delete_object_store("bucket").await.unwrap();
+        jetstream.get_object_store("bucket").await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn delete() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        let bucket = jetstream
+            .create_object_store(Config {
+                bucket: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        bucket
+            .put(
+                ObjectMeta {
+                    name: "FOO".to_string(),
+                    description: Some("foo object".to_string()),
+                    ..Default::default()
+                },
+                &mut vec![1, 2, 3].as_slice(),
+            )
+            .await
+            .unwrap();
+
+        bucket.delete("FOO").await.unwrap();
+
+        let info = bucket.info("FOO").await.unwrap();
+        assert!(info.deleted);
+        assert_eq!(info.size, 0);
+        assert_eq!(info.chunks, 0);
+        assert_eq!(info.description, Some("foo object".to_string()));
+
+        let mut result = Vec::new();
+        bucket
+            .get("FOO")
+            .await
+            .unwrap()
+            .read_to_end(&mut result)
+            .await
+            .unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[tokio::test]
+    async fn seal() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        let bucket = jetstream
+            .create_object_store(Config {
+                bucket: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        bucket.seal().await.unwrap();
+
+        let stream = jetstream.get_stream("OBJ_bucket").await.unwrap();
+        assert!(stream.cached_info().config.sealed);
+
+        bucket.put("FOO", &mut "data".as_bytes()).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn watch() {
+        let server = nats_server::run_server("tests/configs/jetstream.conf");
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+        let jetstream = async_nats::jetstream::new(client);
+
+        let bucket = jetstream
+            .create_object_store(Config {
+                bucket: "bucket".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        bucket.put("FOO", &mut "first".as_bytes()).await.unwrap();
+
+        let mut watch = bucket.watch().await.unwrap();
+
+        let info = watch.next().await.unwrap().unwrap();
+        assert_eq!(info.name, "FOO");
+        assert_eq!(info.size, 5);
+
+        bucket.put("BAR", &mut "second".as_bytes()).await.unwrap();
+        let info = watch.next().await.unwrap().unwrap();
+        assert_eq!(info.name, "BAR");
+        assert_eq!(info.size, 6);
+
+        bucket.delete("FOO").await.unwrap();
+        let info = watch.next().await.unwrap().unwrap();
+        assert_eq!(info.name, "FOO");
+        assert!(info.deleted);
+    }
+}
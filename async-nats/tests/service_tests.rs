@@ -0,0 +1,241 @@
+// Copyright 2020-2022 The NATS Authors
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod service {
+    use async_nats::service::{self, Info, PingResponse, SchemaResponse, Stats, Verb};
+    use futures::StreamExt;
+
+    fn config() -> service::Config {
+        service::Config {
+            name: "generator".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("generates things".to_string()),
+            schema: Some(service::Schema {
+                request: "string".to_string(),
+                response: "number".to_string(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_config() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let err = service::add(
+            client.clone(),
+            service::Config {
+                name: "generator.v1".to_string(),
+                ..config()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, service::AddError::InvalidName));
+
+        let err = service::add(
+            client,
+            service::Config {
+                version: "1.0".to_string(),
+                ..config()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, service::AddError::InvalidVersion));
+    }
+
+    #[tokio::test]
+    async fn discovery() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let service = service::add(client.clone(), config()).await.unwrap();
+        let _endpoint = service.endpoint("generator.length").await.unwrap();
+        let id = service.id();
+
+        for (name, id) in [
+            (None, None),
+            (Some("generator"), None),
+            (Some("generator"), Some(id.as_str())),
+        ] {
+            let subject = service::verb_subject(Verb::Ping, name, id);
+            let response = client.request(subject, "".into()).await.unwrap();
+            let ping: PingResponse = serde_json::from_slice(&response.payload).unwrap();
+            assert_eq!(ping.kind, "io.nats.micro.v1.ping_response");
+            assert_eq!(ping.name, "generator");
+            assert_eq!(ping.id, service.id());
+            assert_eq!(ping.version, "1.0.0");
+        }
+
+        let response = client
+            .request("$SRV.INFO.generator".into(), "".into())
+            .await
+            .unwrap();
+        let info: Info = serde_json::from_slice(&response.payload).unwrap();
+        assert_eq!(info, service.info());
+        assert_eq!(info.description, Some("generates things".to_string()));
+        assert_eq!(info.subjects, vec!["generator.length".to_string()]);
+
+        let response = client
+            .request("$SRV.SCHEMA.generator".into(), "".into())
+            .await
+            .unwrap();
+        let schema: SchemaResponse = serde_json::from_slice(&response.payload).unwrap();
+        assert_eq!(schema.schema, config().schema);
+
+        // a different service name is not answered.
+        client
+            .request("$SRV.PING.other".into(), "".into())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn endpoint_stats() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let service = service::add(client.clone(), config()).await.unwrap();
+        let mut endpoint = service.endpoint("generator.length").await.unwrap();
+        // the stats of an endpoint would be replaced by adding it again.
+        assert!(matches!(
+            service.endpoint("generator.length").await.unwrap_err(),
+            service::EndpointError::AlreadyExists
+        ));
+        tokio::spawn(async move {
+            while let Some(request) = endpoint.next().await {
+                let response = match std::str::from_utf8(&request.message.payload) {
+                    Ok(text) => Ok(text.len().to_string().into()),
+                    Err(err) => Err(service::Error {
+                        code: 400,
+                        description: format!("invalid text\n{}", err),
+                    }),
+                };
+                request.respond(response).await.unwrap();
+            }
+        });
+
+        let response = client
+            .request("generator.length".into(), "hello".into())
+            .await
+            .unwrap();
+        assert_eq!(response.payload, "5");
+        assert!(response.service_error().is_none());
+
+        let response = client
+            .request("generator.length".into(), vec![0xff, 0xfe].into())
+            .await
+            .unwrap();
+        let err = response.service_error().unwrap();
+        assert_eq!(err.code, 400);
+        // line breaks cannot be sent in a header.
+        assert!(err.description.starts_with("invalid text "));
+        assert!(response.payload.is_empty());
+
+        let response = client
+            .request("$SRV.STATS".into(), "".into())
+            .await
+            .unwrap();
+        let stats: Stats = serde_json::from_slice(&response.payload).unwrap();
+        assert_eq!(stats.kind, "io.nats.micro.v1.stats_response");
+        assert_eq!(stats.endpoints.len(), 1);
+        let endpoint = &stats.endpoints[0];
+        assert_eq!(endpoint.subject, "generator.length");
+        assert_eq!(endpoint.num_requests, 2);
+        assert_eq!(endpoint.num_errors, 1);
+        assert!(endpoint
+            .last_error
+            .as_ref()
+            .unwrap()
+            .starts_with("400:invalid text"));
+        assert!(endpoint.processing_time >= endpoint.average_processing_time);
+
+        // requests which are not responded to do not lower the average processing time.
+        let mut partial = service.endpoint("generator.partial").await.unwrap();
+        client
+            .publish("generator.partial".into(), "".into())
+            .await
+            .unwrap();
+        partial.next().await.unwrap();
+        let response = tokio::spawn({
+            let client = client.clone();
+            async move { client.request("generator.partial".into(), "".into()).await }
+        });
+        partial
+            .next()
+            .await
+            .unwrap()
+            .respond(Ok("".into()))
+            .await
+            .unwrap();
+        response.await.unwrap().unwrap();
+        let stats = service.stats();
+        let endpoint = stats
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.subject == "generator.partial")
+            .unwrap();
+        assert_eq!(endpoint.num_requests, 2);
+        assert_eq!(endpoint.average_processing_time, endpoint.processing_time);
+
+        service.reset();
+        let stats = service.stats();
+        assert_eq!(stats.endpoints[0].num_requests, 0);
+        assert_eq!(stats.endpoints[0].last_error, None);
+        assert_eq!(stats.endpoints[1].num_requests, 0);
+    }
+
+    #[tokio::test]
+    async fn stop() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let service = service::add(client.clone(), config()).await.unwrap();
+        let mut endpoint = service.endpoint("generator.length").await.unwrap();
+        service.stop().await.unwrap();
+
+        assert!(endpoint.next().await.is_none());
+        client
+            .request("$SRV.PING".into(), "".into())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn queue_group() {
+        let server = nats_server::run_basic_server();
+        let client = async_nats::connect(server.client_url()).await.unwrap();
+
+        let first = service::add(client.clone(), config()).await.unwrap();
+        let second = service::add(client.clone(), config()).await.unwrap();
+        let mut endpoints = futures::stream::select(
+            first.endpoint("generator.length").await.unwrap(),
+            second.endpoint("generator.length").await.unwrap(),
+        );
+
+        for _ in 0..10 {
+            client
+                .publish_with_reply("generator.length".into(), "reply".into(), "".into())
+                .await
+                .unwrap();
+        }
+        for _ in 0..10 {
+            endpoints.next().await.unwrap();
+        }
+
+        let handled =
+            first.stats().endpoints[0].num_requests + second.stats().endpoints[0].num_requests;
+        assert_eq!(handled, 10);
+    }
+}
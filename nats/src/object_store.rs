@@ -16,13 +16,14 @@
 
 use crate::header::HeaderMap;
 use crate::jetstream::{
-    DateTime, DiscardPolicy, JetStream, PushSubscription, StorageType, StreamConfig,
+    DateTime, DiscardPolicy, JetStream, PushSubscription, StorageType, StreamConfig, StreamInfo,
     SubscribeOptions,
 };
 use crate::Message;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::cmp;
 use std::io;
 use std::time::Duration;
@@ -32,6 +33,7 @@ use time::OffsetDateTime;
 const DEFAULT_CHUNK_SIZE: usize = 128 * 1024;
 const NATS_ROLLUP: &str = "Nats-Rollup";
 const ROLLUP_SUBJECT: &str = "sub";
+const DIGEST_PREFIX: &str = "SHA-256=";
 
 lazy_static! {
     static ref BUCKET_NAME_RE: Regex = Regex::new(r#"\A[a-zA-Z0-9_-]+\z"#).unwrap();
@@ -54,6 +56,14 @@ fn sanitize_object_name(object_name: &str) -> String {
     object_name.replace('.', "_").replace(' ', "_")
 }
 
+fn encode_digest(digest: Sha256) -> String {
+    format!(
+        "{}{}",
+        DIGEST_PREFIX,
+        base64::encode_config(digest.finalize(), base64::URL_SAFE)
+    )
+}
+
 /// Configuration values for object store buckets.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -262,6 +272,9 @@ pub struct Object {
     subscription: PushSubscription,
     remaining_bytes: Vec<u8>,
     has_pending_messages: bool,
+    digest: Option<Sha256>,
+    // set once the digest did not match, failing every later read.
+    digest_mismatch: Option<String>,
 }
 
 impl Object {
@@ -271,6 +284,8 @@ impl Object {
             info,
             remaining_bytes: Vec::new(),
             has_pending_messages: true,
+            digest: Some(Sha256::new()),
+            digest_mismatch: None,
         }
     }
 
@@ -278,11 +293,38 @@ impl Object {
     pub fn info(&self) -> &ObjectInfo {
         &self.info
     }
+
+    /// Compares the digest of all chunks read so far with the one stored in the object info.
+    /// Objects stored without a digest are not verified.
+    fn verify_digest(&mut self) -> io::Result<()> {
+        if let Some(digest) = self.digest.take() {
+            if self.info.digest.is_empty() {
+                return Ok(());
+            }
+
+            let digest = encode_digest(digest);
+            if digest != self.info.digest {
+                self.digest_mismatch = Some(format!(
+                    "object digest mismatch: expected {}, got {}",
+                    self.info.digest, digest
+                ));
+            }
+        }
+
+        match self.digest_mismatch {
+            Some(ref mismatch) => Err(io::Error::new(io::ErrorKind::InvalidData, mismatch.clone())),
+            None => Ok(()),
+        }
+    }
 }
 
 impl io::Read for Object {
     /// Read the data chunks for a given Object from attached subscription and copy it to provided buffer.
     fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if let Some(ref mismatch) = self.digest_mismatch {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, mismatch.clone()));
+        }
+
         // read data accumulated in remaining bytes into the buffer.
         if !self.remaining_bytes.is_empty() {
             let len = cmp::min(buffer.len(), self.remaining_bytes.len());
@@ -296,15 +338,21 @@ impl io::Read for Object {
         if self.has_pending_messages {
             let maybe_message = self.subscription.next();
             if let Some(message) = maybe_message {
-                let len = cmp::min(buffer.len(), message.data.len());
-                buffer[..len].copy_from_slice(&message.data[..len]);
-                self.remaining_bytes.extend_from_slice(&message.data[len..]);
+                if let Some(digest) = self.digest.as_mut() {
+                    digest.update(&message.data);
+                }
 
                 if let Some(message_info) = message.jetstream_message_info() {
                     if message_info.pending == 0 {
                         self.has_pending_messages = false;
+                        self.verify_digest()?;
                     }
                 }
+
+                let len = cmp::min(buffer.len(), message.data.len());
+                buffer[..len].copy_from_slice(&message.data[..len]);
+                self.remaining_bytes.extend_from_slice(&message.data[len..]);
+
                 return Ok(len);
             }
         }
@@ -426,6 +474,7 @@ impl ObjectStore {
         let mut object_size = 0;
 
         let mut buffer = [0; DEFAULT_CHUNK_SIZE];
+        let mut digest = Sha256::new();
 
         loop {
             let n = data.read(&mut buffer)?;
@@ -435,6 +484,7 @@ impl ObjectStore {
 
             object_size += n;
             object_chunks += 1;
+            digest.update(&buffer[..n]);
 
             self.context.publish(&chunk_subject, &buffer[..n])?;
         }
@@ -449,7 +499,7 @@ impl ObjectStore {
             nuid: object_nuid,
             chunks: object_chunks,
             size: object_size,
-            digest: "".to_string(),
+            digest: encode_digest(digest),
             modified: OffsetDateTime::now_utc(),
             deleted: false,
         };
@@ -504,9 +554,43 @@ impl ObjectStore {
     pub fn get(&self, object_name: &str) -> io::Result<Object> {
         let object_info = self.info(object_name)?;
         if let Some(link) = object_info.link {
-            return self.get(&link.name);
+            if link.name.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "object is a link to a bucket",
+                ));
+            }
+
+            // Links may point to objects in other buckets.
+            let linked_store = match link.bucket {
+                Some(ref bucket) if bucket != &self.name => {
+                    Some(self.context.object_store(bucket)?)
+                }
+                _ => None,
+            };
+            let store = linked_store.as_ref().unwrap_or(self);
+
+            let linked_info = store.info(&link.name)?;
+            if linked_info.deleted {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "linked object is deleted",
+                ));
+            }
+            if linked_info.link.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "link points to another link",
+                ));
+            }
+
+            return store.get_chunks(linked_info);
         }
 
+        self.get_chunks(object_info)
+    }
+
+    fn get_chunks(&self, object_info: ObjectInfo) -> io::Result<Object> {
         let chunk_subject = format!("$O.{}.C.{}", self.name, object_info.nuid);
         let subscription = self
             .context
@@ -515,6 +599,122 @@ impl ObjectStore {
         Ok(Object::new(subscription, object_info))
     }
 
+    /// Adds a link to an existing object under the given name.
+    /// Links to other links are not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// # use nats::object_store::Config;
+    /// # fn main() -> std::io::Result<()> {
+    /// # let client = nats::connect("demo.nats.io")?;
+    /// # let context = nats::jetstream::new(client);
+    /// #
+    /// let bucket = context.create_object_store(&Config {
+    ///   bucket: "add_link".to_string(),
+    ///   ..Default::default()
+    /// })?;
+    ///
+    /// let bytes = vec![0, 1, 2, 3, 4];
+    /// let object = bucket.put("foo", &mut bytes.as_slice())?;
+    ///
+    /// bucket.add_link("latest", &object)?;
+    ///
+    /// let mut result = Vec::new();
+    /// bucket.get("latest")?.read_to_end(&mut result)?;
+    /// assert_eq!(result, bytes);
+    ///
+    /// # context.delete_object_store("add_link")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_link(&self, name: &str, object: &ObjectInfo) -> io::Result<ObjectInfo> {
+        if object.name.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "object name is required",
+            ));
+        }
+        if object.deleted {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "object is deleted",
+            ));
+        }
+        if object.link.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "adding links to links is not allowed",
+            ));
+        }
+
+        self.put_link(
+            name,
+            ObjectLink {
+                name: object.name.clone(),
+                bucket: Some(object.bucket.clone()),
+            },
+        )
+    }
+
+    /// Adds a link to another object store bucket under the given name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nats::object_store::Config;
+    /// # fn main() -> std::io::Result<()> {
+    /// # let client = nats::connect("demo.nats.io")?;
+    /// # let context = nats::jetstream::new(client);
+    /// #
+    /// let bucket = context.create_object_store(&Config {
+    ///   bucket: "add_bucket_link".to_string(),
+    ///   ..Default::default()
+    /// })?;
+    /// let other = context.create_object_store(&Config {
+    ///   bucket: "add_bucket_link_other".to_string(),
+    ///   ..Default::default()
+    /// })?;
+    ///
+    /// let info = bucket.add_bucket_link("other", &other)?;
+    /// assert_eq!(info.link.unwrap().bucket.unwrap(), "add_bucket_link_other");
+    ///
+    /// # context.delete_object_store("add_bucket_link")?;
+    /// # context.delete_object_store("add_bucket_link_other")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_bucket_link(&self, name: &str, bucket: &ObjectStore) -> io::Result<ObjectInfo> {
+        self.put_link(
+            name,
+            ObjectLink {
+                name: "".to_string(),
+                bucket: Some(bucket.name.clone()),
+            },
+        )
+    }
+
+    fn put_link(&self, name: &str, link: ObjectLink) -> io::Result<ObjectInfo> {
+        // Do not overwrite regular objects with a link.
+        if let Ok(existing_object_info) = self.info(name) {
+            if existing_object_info.link.is_none() && !existing_object_info.deleted {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "an object already exists with that name",
+                ));
+            }
+        }
+
+        let object_meta = ObjectMeta {
+            name: name.to_string(),
+            description: None,
+            link: Some(link),
+        };
+
+        self.put(object_meta, &mut io::empty())
+    }
+
     /// Places a delete marker and purges the data stream associated with the key.
     ///
     /// # Example
@@ -615,6 +815,166 @@ impl ObjectStore {
 
         Ok(Watch { subscription })
     }
+
+    /// Returns an iterator over the info of all objects in the store, skipping deleted ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nats::object_store::Config;
+    /// # fn main() -> std::io::Result<()> {
+    /// # let client = nats::connect("demo.nats.io")?;
+    /// # let context = nats::jetstream::new(client);
+    /// #
+    /// let bucket = context.create_object_store(&Config {
+    ///   bucket: "list".to_string(),
+    ///   ..Default::default()
+    /// })?;
+    ///
+    /// bucket.put("foo", &mut vec![0, 1, 2].as_slice())?;
+    /// bucket.put("bar", &mut vec![3, 4, 5].as_slice())?;
+    ///
+    /// let names: Vec<String> = bucket.list()?.map(|info| info.name).collect();
+    /// assert_eq!(names, vec!["foo", "bar"]);
+    ///
+    /// # context.delete_object_store("list")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list(&self) -> io::Result<List> {
+        let subject = format!("$O.{}.M.>", &self.name);
+        let subscription = self.context.subscribe_with_options(
+            &subject,
+            &SubscribeOptions::ordered().deliver_last_per_subject(),
+        )?;
+
+        // An empty bucket would never deliver any message, so check upfront.
+        let done = subscription.consumer_info()?.num_pending == 0;
+
+        Ok(List { subscription, done })
+    }
+
+    /// Returns the status of the object store, including its backing stream info.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use nats::object_store::Config;
+    /// # fn main() -> std::io::Result<()> {
+    /// # let client = nats::connect("demo.nats.io")?;
+    /// # let context = nats::jetstream::new(client);
+    /// #
+    /// let bucket = context.create_object_store(&Config {
+    ///   bucket: "status".to_string(),
+    ///   ..Default::default()
+    /// })?;
+    ///
+    /// let status = bucket.status()?;
+    /// assert_eq!(status.bucket(), "status");
+    /// assert!(!status.sealed());
+    ///
+    /// # context.delete_object_store("status")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn status(&self) -> io::Result<ObjectStoreStatus> {
+        let stream_name = format!("OBJ_{}", self.name);
+        let info = self.context.stream_info(stream_name)?;
+
+        Ok(ObjectStoreStatus {
+            bucket: self.name.clone(),
+            info,
+        })
+    }
+}
+
+/// Status of an object store bucket.
+#[derive(Debug, Clone)]
+pub struct ObjectStoreStatus {
+    bucket: String,
+    info: StreamInfo,
+}
+
+impl ObjectStoreStatus {
+    /// The name of the bucket
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// A short description of the bucket, if any
+    pub fn description(&self) -> Option<&str> {
+        self.info.config.description.as_deref()
+    }
+
+    /// Whether the bucket is sealed from further modifications
+    pub fn sealed(&self) -> bool {
+        self.info.config.sealed
+    }
+
+    /// Total size of the bucket in bytes, including meta data
+    pub fn size(&self) -> u64 {
+        self.info.state.bytes
+    }
+
+    /// How long the bucket keeps objects for
+    pub fn max_age(&self) -> Duration {
+        self.info.config.max_age
+    }
+
+    /// The type of storage backend of the bucket
+    pub fn storage(&self) -> StorageType {
+        self.info.config.storage
+    }
+
+    /// How many replicas are kept for each object
+    pub fn replicas(&self) -> usize {
+        self.info.config.num_replicas
+    }
+
+    /// Info of the stream backing the bucket
+    pub fn info(&self) -> &StreamInfo {
+        &self.info
+    }
+}
+
+/// Iterator returned by `list`
+pub struct List {
+    subscription: PushSubscription,
+    done: bool,
+}
+
+impl Iterator for List {
+    type Item = ObjectInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+            return match self.subscription.next() {
+                Some(message) => {
+                    // If there are no more pending messages we'll stop after delivering the info
+                    // derived from this message.
+                    if let Some(info) = message.jetstream_message_info() {
+                        if info.pending == 0 {
+                            self.done = true;
+                        }
+                    }
+
+                    let object_info: ObjectInfo = match serde_json::from_slice(&message.data) {
+                        Ok(object_info) => object_info,
+                        Err(_) => continue,
+                    };
+                    if object_info.deleted {
+                        continue;
+                    }
+
+                    Some(object_info)
+                }
+                None => None,
+            };
+        }
+    }
 }
 
 /// Iterator returned by `watch`
@@ -235,3 +235,153 @@ fn object_watch() {
     assert_eq!(info.name, "bar");
     assert_eq!(info.size, bytes.len());
 }
+
+#[test]
+fn object_digest() {
+    let server = nats_server::run_server("tests/configs/jetstream.conf");
+    let client = nats::connect(&server.client_url()).unwrap();
+    let context = nats::jetstream::new(client);
+
+    let bucket = context
+        .create_object_store(&nats::object_store::Config {
+            bucket: "DIGEST".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let object_info = bucket.put("A", &mut "hello world".as_bytes()).unwrap();
+    assert_eq!(
+        object_info.digest,
+        "SHA-256=uU0nuZNNPgilLlLX2n2r-sSE7-N6U4DukIj3rOLvzek="
+    );
+
+    let mut result = Vec::new();
+    bucket.get("A").unwrap().read_to_end(&mut result).unwrap();
+    assert_eq!(result, b"hello world");
+
+    // Tamper with the stored digest, reading the object should now fail.
+    let mut object_info = bucket.info("A").unwrap();
+    object_info.digest = "SHA-256=invalid".to_string();
+    context
+        .publish("$O.DIGEST.M.A", serde_json::to_vec(&object_info).unwrap())
+        .unwrap();
+
+    let mut object = bucket.get("A").unwrap();
+    let mut result = Vec::new();
+    let err = object.read_to_end(&mut result).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // retrying does not end the object early.
+    let err = object.read(&mut [0; 16]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn object_links() {
+    let server = nats_server::run_server("tests/configs/jetstream.conf");
+    let client = nats::connect(&server.client_url()).unwrap();
+    let context = nats::jetstream::new(client);
+
+    let bucket = context
+        .create_object_store(&nats::object_store::Config {
+            bucket: "LINKS".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+    let other_bucket = context
+        .create_object_store(&nats::object_store::Config {
+            bucket: "OTHER".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let bytes = b"release".repeat(100);
+    let object_info = bucket.put("A", &mut bytes.as_slice()).unwrap();
+    let other_info = other_bucket.put("B", &mut "other".as_bytes()).unwrap();
+
+    // Links within the same bucket.
+    let link_info = bucket.add_link("latest", &object_info).unwrap();
+    let link = link_info.link.unwrap();
+    assert_eq!(link.name, "A");
+    assert_eq!(link.bucket.unwrap(), "LINKS");
+
+    let mut result = Vec::new();
+    bucket
+        .get("latest")
+        .unwrap()
+        .read_to_end(&mut result)
+        .unwrap();
+    assert_eq!(result, bytes);
+
+    // Links across buckets.
+    bucket.add_link("other", &other_info).unwrap();
+    let mut result = Vec::new();
+    bucket
+        .get("other")
+        .unwrap()
+        .read_to_end(&mut result)
+        .unwrap();
+    assert_eq!(result, b"other");
+
+    // Links to links are rejected.
+    let link_info = bucket.info("latest").unwrap();
+    bucket.add_link("chain", &link_info).unwrap_err();
+
+    // Regular objects are not overwritten by links.
+    bucket.add_link("A", &other_info).unwrap_err();
+
+    // Bucket links can be created, but not read as objects.
+    let bucket_link = bucket.add_bucket_link("bucket", &other_bucket).unwrap();
+    let link = bucket_link.link.unwrap();
+    assert!(link.name.is_empty());
+    assert_eq!(link.bucket.unwrap(), "OTHER");
+    assert!(bucket.get("bucket").is_err());
+
+    // Links to deleted objects are not found.
+    other_bucket.delete("B").unwrap();
+    let err = bucket.get("other").err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn object_list_and_status() {
+    let server = nats_server::run_server("tests/configs/jetstream.conf");
+    let client = nats::connect(&server.client_url()).unwrap();
+    let context = nats::jetstream::new(client);
+
+    let bucket = context
+        .create_object_store(&nats::object_store::Config {
+            bucket: "LIST".to_string(),
+            description: Some("listing".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(bucket.list().unwrap().count(), 0);
+
+    let status = bucket.status().unwrap();
+    assert_eq!(status.bucket(), "LIST");
+    assert_eq!(status.description(), Some("listing"));
+    assert!(!status.sealed());
+    assert_eq!(status.size(), 0);
+
+    bucket.put("A", &mut "a".repeat(100).as_bytes()).unwrap();
+    bucket.put("B", &mut "b".repeat(200).as_bytes()).unwrap();
+    bucket.put("C", &mut "c".repeat(300).as_bytes()).unwrap();
+    bucket.put("B", &mut "b".repeat(20).as_bytes()).unwrap();
+    bucket.delete("C").unwrap();
+
+    let objects: Vec<nats::object_store::ObjectInfo> = bucket.list().unwrap().collect();
+    assert_eq!(objects.len(), 2);
+    assert_eq!(objects[0].name, "A");
+    assert_eq!(objects[0].size, 100);
+    assert_eq!(objects[1].name, "B");
+    assert_eq!(objects[1].size, 20);
+
+    let status = bucket.status().unwrap();
+    assert!(status.size() > 0);
+    assert_eq!(status.info().config.name, "OBJ_LIST");
+
+    bucket.seal().unwrap();
+    assert!(bucket.status().unwrap().sealed());
+}